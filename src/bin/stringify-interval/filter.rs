@@ -0,0 +1,194 @@
+//! Rewriting the durations found in text, like in log lines, into their text.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use chrono::{DateTime, Duration, Months, Utc};
+use regex::{Captures, Regex};
+use serde::Deserialize;
+
+/// A regular expression for durations in text. It has either an `iso` group for an ISO 8601 duration, or a `value` group for a number and a `unit` group for its unit, like "ms" or "min". Only the text the groups cover is replaced, so `elapsed=(?<value>\d+)(?<unit>s)` keeps the "elapsed=".
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub(crate) struct Pattern(Regex);
+
+impl Pattern {
+	pub(crate) fn new(pattern: &str) -> Result<Self, String> {
+		let regex = Regex::new(pattern).map_err(|error| error.to_string())?;
+		let names: Vec<_> = regex.capture_names().flatten().collect();
+		let has = |name| names.contains(&name);
+		if has("iso") || (has("value") && has("unit")) {
+			Ok(Self(regex))
+		} else {
+			Err(String::from(
+				"expected an \"iso\" group, or a \"value\" and a \"unit\" group",
+			))
+		}
+	}
+	/// Numbers with a unit right after them, like "734512ms" or "8123s", and ISO 8601 durations, like "PT2H5M".
+	pub(crate) fn defaults() -> Vec<Self> {
+		[
+			r"\b(?<value>[0-9]+(?:\.[0-9]+)?)(?<unit>ns|us|µs|ms|s|min|h)\b",
+			r"\b(?<iso>P(?:[0-9]+[YMWD])*(?:T(?:[0-9]+(?:[.,][0-9]+)?[HMS])+)?)\b",
+		]
+		.into_iter()
+		.map(|pattern| Self::new(pattern).expect("the default patterns are valid"))
+		.collect()
+	}
+	/// The text to replace, and the duration in it.
+	fn duration(
+		&self,
+		captures: &Captures,
+		now: DateTime<Utc>,
+	) -> Option<(Range<usize>, Duration)> {
+		let groups: Vec<_> = ["iso", "value", "unit"]
+			.into_iter()
+			.filter_map(|name| captures.name(name))
+			.collect();
+		let start = groups.iter().map(|group| group.start()).min()?;
+		let end = groups.iter().map(|group| group.end()).max()?;
+		let interval = match captures.name("iso") {
+			Some(iso) => iso_duration(iso.as_str(), now)?,
+			None => {
+				let unit = unit_length(captures.name("unit")?.as_str())?;
+				parse_count(captures.name("value")?.as_str(), unit)?
+			}
+		};
+		Some((start..end, interval))
+	}
+}
+
+impl TryFrom<String> for Pattern {
+	type Error = String;
+
+	fn try_from(pattern: String) -> Result<Self, Self::Error> {
+		Self::new(&pattern)
+	}
+}
+
+/// Replaces every duration `patterns` find in `line` with its text from `stringify`, and leaves the ones that can't be read or stringified as they are. When matches overlap, the one from the earlier pattern wins.
+pub(crate) fn rewrite<'l, F>(
+	line: &'l str,
+	patterns: &[Pattern],
+	now: DateTime<Utc>,
+	stringify: F,
+) -> Cow<'l, str>
+where
+	F: Fn(Duration) -> Option<String>,
+{
+	let mut replacements: Vec<(Range<usize>, String)> = Vec::new();
+	for pattern in patterns {
+		for captures in pattern.0.captures_iter(line) {
+			let Some((range, interval)) = pattern.duration(&captures, now) else {
+				continue;
+			};
+			if replacements
+				.iter()
+				.any(|(other, _)| range.start < other.end && other.start < range.end)
+			{
+				continue;
+			}
+			if let Some(text) = stringify(interval) {
+				replacements.push((range, text));
+			}
+		}
+	}
+	if replacements.is_empty() {
+		return Cow::Borrowed(line);
+	}
+	replacements.sort_by_key(|(range, _)| range.start);
+
+	let mut output = String::with_capacity(line.len());
+	let mut copied = 0;
+	for (range, text) in replacements {
+		output.push_str(&line[copied..range.start]);
+		output.push_str(&text);
+		copied = range.end;
+	}
+	output.push_str(&line[copied..]);
+	Cow::Owned(output)
+}
+
+/// A count like "12" or "1.5" of a unit `unit` long, with "." or "," before the decimals, rounded down to the nanosecond. Anything else isn't a count, even if a custom pattern matched it.
+fn parse_count(count: &str, unit: Duration) -> Option<Duration> {
+	let (whole, fraction) = match count.split_once(['.', ',']) {
+		Some((_, "")) => return None,
+		Some((whole, fraction)) => (whole, fraction),
+		None => (count, ""),
+	};
+	if whole.is_empty() || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+		return None;
+	}
+	let per_unit = i128::from(unit.num_nanoseconds()?);
+	let mut nanoseconds = whole.parse::<i128>().ok()?.checked_mul(per_unit)?;
+	// Digits past the 18th are too small to matter, and fewer keep the product in range.
+	let fraction = &fraction[..fraction.len().min(18)];
+	if !fraction.is_empty() {
+		let digits: i128 = fraction.parse().ok()?;
+		nanoseconds = nanoseconds.checked_add(digits * per_unit / 10_i128.pow(fraction.len() as u32))?;
+	}
+	i64::try_from(nanoseconds).ok().map(Duration::nanoseconds)
+}
+
+fn unit_length(unit: &str) -> Option<Duration> {
+	Some(match unit {
+		"ns" => Duration::nanoseconds(1),
+		"us" | "µs" => Duration::microseconds(1),
+		"ms" => Duration::milliseconds(1),
+		"s" | "sec" | "secs" | "second" | "seconds" => Duration::seconds(1),
+		"m" | "min" | "mins" | "minute" | "minutes" => Duration::minutes(1),
+		"h" | "hr" | "hrs" | "hour" | "hours" => Duration::hours(1),
+		"d" | "day" | "days" => Duration::days(1),
+		"w" | "week" | "weeks" => Duration::weeks(1),
+		_ => return None,
+	})
+}
+
+/// An ISO 8601 duration like "P1Y2M10DT2H30M", as the interval from `now` to that much later, since years and months aren't always as long.
+fn iso_duration(input: &str, now: DateTime<Utc>) -> Option<Duration> {
+	let rest = input.strip_prefix('P')?;
+	let (date, time) = rest.split_once('T').unwrap_or((rest, ""));
+	let mut months: u32 = 0;
+	let mut total = Duration::zero();
+	let mut any = false;
+	for (part, is_time) in [(date, false), (time, true)] {
+		let mut start = 0;
+		for (index, designator) in part.char_indices() {
+			if designator.is_ascii_digit() || designator == '.' || designator == ',' {
+				continue;
+			}
+			let value = &part[start..index];
+			start = index + designator.len_utf8();
+			any = true;
+			let unit = match (is_time, designator) {
+				(false, 'Y' | 'M') => {
+					let count: u32 = value.parse().ok()?;
+					let count = if designator == 'Y' {
+						count.checked_mul(12)?
+					} else {
+						count
+					};
+					months = months.checked_add(count)?;
+					continue;
+				}
+				(false, 'W') => Duration::weeks(1),
+				(false, 'D') => Duration::days(1),
+				(true, 'H') => Duration::hours(1),
+				(true, 'M') => Duration::minutes(1),
+				(true, 'S') => Duration::seconds(1),
+				_ => return None,
+			};
+			total = total.checked_add(&parse_count(value, unit)?)?;
+		}
+		if start != part.len() {
+			return None;
+		}
+	}
+	if !any {
+		return None;
+	}
+	let end = now
+		.checked_add_months(Months::new(months))?
+		.checked_add_signed(total)?;
+	Some(end - now)
+}
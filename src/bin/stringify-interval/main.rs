@@ -0,0 +1,311 @@
+//! Formats intervals given in seconds, or since or until a date, parses text back into seconds, and rewrites the durations in text like logs.
+
+use std::borrow::Cow;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, Utc};
+use clap::{Parser, ValueEnum};
+use serde::Deserialize;
+use stringify_interval::{DisplayConfig, Phrasing, Text};
+
+use crate::filter::Pattern;
+
+mod filter;
+mod tests;
+
+#[derive(Debug, Parser)]
+#[command(
+	version,
+	about = "Turns seconds into text like \"1 day, 10 hours and 17 minutes\", and back",
+	after_help = "Examples:
+  stringify-interval 123456                     1 day, 10 hours and 18 minutes
+  stringify-interval --style compact 93784      1d 2h 3m
+  stringify-interval --since 2024-01-01 --relative --locale de
+  stringify-interval --parse \"2 days\"           172800
+  some-command | stringify-interval --json      one JSON object per line
+  tail -f app.log | stringify-interval --filter \"took 734512ms\" becomes \"took 12 minutes\""
+)]
+struct Args {
+	/// Intervals in seconds, or text to parse with --parse. Without any, they are read from stdin, one per line.
+	inputs: Vec<String>,
+	/// Show the time since this date, given as YYYY-MM-DD or in RFC 3339.
+	#[arg(long, value_parser = parse_date, conflicts_with_all = ["until", "parse", "inputs"])]
+	since: Option<DateTime<FixedOffset>>,
+	/// Show the time until this date, given as YYYY-MM-DD or in RFC 3339.
+	#[arg(long, value_parser = parse_date, conflicts_with_all = ["parse", "inputs"])]
+	until: Option<DateTime<FixedOffset>>,
+	/// Parse text like "2 days" into seconds instead.
+	#[arg(long)]
+	parse: bool,
+	/// Copy stdin to stdout, with the durations in it like "734512ms", "8123s" or "PT2H5M" replaced by their text. Durations under a second are left alone.
+	#[arg(long, conflicts_with_all = ["since", "until", "parse", "inputs", "json"])]
+	filter: bool,
+	/// A regular expression for the durations to replace with --filter, instead of the default ones. It needs an "iso" group for an ISO 8601 duration, or a "value" group for the number and a "unit" group for a unit like "ms", "s", "min" or "h". Only the text in those groups is replaced. Can be given more than once.
+	#[arg(long = "pattern", value_parser = Pattern::new, requires = "filter")]
+	patterns: Vec<Pattern>,
+	#[arg(long, value_enum)]
+	style: Option<Style>,
+	#[arg(long, value_enum)]
+	locale: Option<Locale>,
+	/// The units to display, like "days,hours,minutes". By default, years down to minutes, and seconds under 10 minutes.
+	#[arg(long, value_enum, value_delimiter = ',')]
+	units: Option<Vec<UnitName>>,
+	/// Phrase intervals relative to now, like "in 3 days" or "3 days ago".
+	#[arg(long)]
+	relative: bool,
+	/// Write each result as a JSON object on its own line.
+	#[arg(long)]
+	json: bool,
+	/// A TOML file with defaults for style, locale, units, relative, json and patterns.
+	#[arg(long)]
+	config: Option<PathBuf>,
+}
+
+/// The defaults that can be read from a file, for anything not given as a flag.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileSettings {
+	style: Option<Style>,
+	locale: Option<Locale>,
+	units: Option<Vec<UnitName>>,
+	relative: bool,
+	json: bool,
+	patterns: Option<Vec<Pattern>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Style {
+	/// Like "1 day, 10 hours and 17 minutes".
+	#[default]
+	Long,
+	/// Like "1d 10h 17m".
+	Compact,
+}
+
+#[derive(Debug, Clone, Copy, Default, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Locale {
+	#[default]
+	En,
+	De,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum UnitName {
+	Centuries,
+	Decades,
+	Years,
+	Quarters,
+	Months,
+	Weeks,
+	Days,
+	Hours,
+	Minutes,
+	Seconds,
+}
+
+/// Everything needed to handle an input, from the flags and the file.
+struct Settings {
+	config: DisplayConfig,
+	text: Text,
+	json: bool,
+	parse: bool,
+	patterns: Vec<Pattern>,
+}
+
+impl Settings {
+	fn new(args: &Args, file: FileSettings) -> Self {
+		let patterns = match (args.patterns.is_empty(), file.patterns) {
+			(false, _) => args.patterns.clone(),
+			(true, Some(patterns)) => patterns,
+			(true, None) => Pattern::defaults(),
+		};
+		let text = match args.style.or(file.style).unwrap_or_default() {
+			Style::Compact => Text::compact(),
+			Style::Long => match args.locale.or(file.locale).unwrap_or_default() {
+				Locale::En => Text::default(),
+				Locale::De => Text::german(),
+			},
+		};
+		let mut config = match args.units.as_ref().or(file.units.as_ref()) {
+			Some(units) => units
+				.iter()
+				.fold(DisplayConfig::none(), |config, unit| match unit {
+					UnitName::Centuries => config.with_centuries(),
+					UnitName::Decades => config.with_decades(),
+					UnitName::Years => config.with_years(),
+					UnitName::Quarters => config.with_quarters(),
+					UnitName::Months => config.with_months(),
+					UnitName::Weeks => config.with_weeks(),
+					UnitName::Days => config.with_days(),
+					UnitName::Hours => config.with_hours(),
+					UnitName::Minutes => config.with_minutes(),
+					UnitName::Seconds => config.with_seconds(),
+				}),
+			None => DisplayConfig::default(),
+		};
+		if args.relative || file.relative {
+			config = config.with_phrasing(Phrasing::Relative);
+		}
+		Self {
+			config,
+			text,
+			json: args.json || file.json,
+			parse: args.parse,
+			patterns,
+		}
+	}
+	/// The output line for an input line, and whether it succeeded.
+	fn handle(&self, input: &str, now: DateTime<Utc>) -> (String, bool) {
+		let input = input.trim();
+		let result = if self.parse {
+			stringify_interval::parse(input, &self.text)
+				.map(Output::Seconds)
+				.map_err(|error| error.to_string())
+		} else {
+			input
+				.parse::<i64>()
+				.ok()
+				.and_then(Duration::try_seconds)
+				.ok_or_else(|| String::from("Expected a whole number of seconds"))
+				.and_then(|interval| self.stringify(interval, now))
+				.map(Output::Text)
+		};
+		self.write(input, result)
+	}
+	/// The line with the durations in it replaced, where they can be stringified. Durations under a second are kept, since they would only become "0 seconds".
+	fn filter<'l>(&self, line: &'l str, now: DateTime<Utc>) -> Cow<'l, str> {
+		filter::rewrite(line, &self.patterns, now, |interval| {
+			if interval.abs() < Duration::seconds(1) {
+				return None;
+			}
+			self.stringify(interval, now).ok()
+		})
+	}
+	fn stringify<Tz: chrono::TimeZone + 'static>(
+		&self,
+		interval: Duration,
+		now: DateTime<Tz>,
+	) -> Result<String, String> {
+		stringify_interval::with_date(interval, now, &self.config, &self.text)
+			.map_err(|error| error.to_string())
+	}
+	fn write(&self, input: &str, result: Result<Output, String>) -> (String, bool) {
+		let success = result.is_ok();
+		let line = match (self.json, result) {
+			(false, Ok(Output::Text(text))) => text,
+			(false, Ok(Output::Seconds(interval))) => format_seconds(interval),
+			(false, Err(error)) => error,
+			(true, Ok(Output::Text(text))) => {
+				serde_json::json!({ "input": input, "text": text }).to_string()
+			}
+			(true, Ok(Output::Seconds(interval))) => {
+				let seconds: serde_json::Value = match interval.subsec_nanos() {
+					0 => interval.num_seconds().into(),
+					nanos => (interval.num_seconds() as f64 + f64::from(nanos) / 1e9).into(),
+				};
+				serde_json::json!({ "input": input, "seconds": seconds }).to_string()
+			}
+			(true, Err(error)) => serde_json::json!({ "input": input, "error": error }).to_string(),
+		};
+		(line, success)
+	}
+}
+
+enum Output {
+	Text(String),
+	Seconds(Duration),
+}
+
+/// Whole seconds as an integer, and otherwise with as many decimals as needed.
+fn format_seconds(interval: Duration) -> String {
+	let sign = if interval < Duration::zero() { "-" } else { "" };
+	let interval = interval.abs();
+	match interval.subsec_nanos() {
+		0 => format!("{sign}{}", interval.num_seconds()),
+		nanos => {
+			let fraction = format!("{nanos:09}");
+			format!(
+				"{sign}{}.{}",
+				interval.num_seconds(),
+				fraction.trim_end_matches('0')
+			)
+		}
+	}
+}
+
+fn parse_date(input: &str) -> Result<DateTime<FixedOffset>, String> {
+	if let Ok(date) = DateTime::parse_from_rfc3339(input) {
+		return Ok(date);
+	}
+	NaiveDate::parse_from_str(input, "%Y-%m-%d")
+		.map(|date| date.and_time(Default::default()).and_utc().fixed_offset())
+		.map_err(|_| String::from("expected a date like 2024-01-01 or 2024-01-01T12:00:00+01:00"))
+}
+
+fn main() -> ExitCode {
+	let args = Args::parse();
+	let file = match &args.config {
+		Some(path) => match std::fs::read_to_string(path)
+			.map_err(|error| error.to_string())
+			.and_then(|contents| toml::from_str(&contents).map_err(|error| error.to_string()))
+		{
+			Ok(file) => file,
+			Err(error) => {
+				eprintln!("Couldn't read {}: {error}", path.display());
+				return ExitCode::FAILURE;
+			}
+		},
+		None => FileSettings::default(),
+	};
+	let settings = Settings::new(&args, file);
+	let now = Utc::now();
+
+	let mut stdout = io::stdout().lock();
+	let mut all_succeeded = true;
+	let mut output = |(line, success): (String, bool)| {
+		all_succeeded &= success;
+		if success || settings.json {
+			writeln!(stdout, "{line}")
+		} else {
+			writeln!(io::stderr(), "{line}")
+		}
+	};
+
+	let result = if let Some(date) = args.since.or(args.until) {
+		let now = now.with_timezone(&date.timezone());
+		let interval = date - now;
+		let result = settings.stringify(interval, now).map(Output::Text);
+		output(settings.write(&date.to_rfc3339(), result))
+	} else if args.filter {
+		// Split on bytes, so the lines keep any carriage return and text that isn't UTF-8 only gets replacement characters.
+		io::stdin().lock().split(b'\n').try_for_each(|line| {
+			let line = String::from_utf8_lossy(&line?).into_owned();
+			output((settings.filter(&line, now).into_owned(), true))
+		})
+	} else if args.inputs.is_empty() {
+		io::stdin()
+			.lock()
+			.lines()
+			.try_for_each(|line| output(settings.handle(&line?, now)))
+	} else {
+		args.inputs
+			.iter()
+			.try_for_each(|input| output(settings.handle(input, now)))
+	};
+
+	match result {
+		// The reader went away, like `head` does, which is fine.
+		Err(error) if error.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+		Err(error) => {
+			eprintln!("{error}");
+			ExitCode::FAILURE
+		}
+		Ok(()) if all_succeeded => ExitCode::SUCCESS,
+		Ok(()) => ExitCode::FAILURE,
+	}
+}
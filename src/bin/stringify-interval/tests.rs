@@ -0,0 +1,135 @@
+#[allow(clippy::module_inception)]
+#[cfg(test)]
+mod tests {
+	use std::borrow::Cow;
+
+	use chrono::{DateTime, Duration, Utc};
+	use clap::Parser;
+
+	use crate::{format_seconds, Args, FileSettings, Settings};
+
+	fn settings(args: &[&str], file: &str) -> Settings {
+		let args = Args::try_parse_from(["stringify-interval"].iter().chain(args)).unwrap();
+		Settings::new(&args, toml::from_str(file).unwrap())
+	}
+
+	fn now() -> DateTime<Utc> {
+		DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+	}
+
+	#[test]
+	fn formats_seconds() {
+		let settings = settings(&[], "");
+		assert_eq!(
+			settings.handle("123456", now()),
+			(String::from("1 day, 10 hours and 18 minutes"), true)
+		);
+		assert_eq!(
+			settings.handle("soon", now()),
+			(String::from("Expected a whole number of seconds"), false)
+		);
+	}
+
+	#[test]
+	fn flags_override_file() {
+		let file = "style = \"compact\"\nunits = [\"hours\", \"minutes\"]\nrelative = true";
+		assert_eq!(settings(&[], file).handle("-5400", now()).0, "1h 30m ago");
+		assert_eq!(
+			settings(&["--style", "long", "--locale", "de"], file)
+				.handle("-5400", now())
+				.0,
+			"vor 1 Stunde und 30 Minuten"
+		);
+		assert!(toml::from_str::<FileSettings>("colour = \"red\"").is_err());
+	}
+
+	#[test]
+	fn parses_to_json() {
+		let settings = settings(&["--parse", "--json"], "");
+		assert_eq!(
+			settings.handle("2 days", now()),
+			(String::from(r#"{"input":"2 days","seconds":172800}"#), true)
+		);
+		assert_eq!(
+			settings.handle("1.5 seconds", now()).0,
+			r#"{"input":"1.5 seconds","seconds":1.5}"#
+		);
+	}
+
+	#[test]
+	fn parse_error_to_json() {
+		let settings = settings(&["--parse", "--json"], "");
+		assert_eq!(
+			settings.handle("2 fortnights", now()),
+			(
+				String::from(
+					r#"{"error":"Unknown unit \"fortnights\", expected one of: weeks, week, days, day, hours, hour, minutes, minute, seconds, second","input":"2 fortnights"}"#
+				),
+				false
+			)
+		);
+	}
+
+	#[test]
+	fn formats_fractional_seconds() {
+		assert_eq!(format_seconds(Duration::milliseconds(-1_250)), "-1.25");
+	}
+
+	#[test]
+	fn filters_durations() {
+		let filter = settings(&["--filter"], "");
+		assert_eq!(
+			filter.filter("took 734512ms, elapsed=8123s, retry in PT2H5M", now()),
+			"took 12 minutes, elapsed=2 hours and 15 minutes, retry in 2 hours and 5 minutes"
+		);
+		assert_eq!(
+			filter.filter("waited 1.5h, then PT0,5M", now()),
+			"waited 1 hour and 30 minutes, then 30 seconds"
+		);
+	}
+
+	#[test]
+	fn filters_calendar_durations() {
+		let filter = settings(&["--filter"], "");
+		assert_eq!(
+			filter.filter("P1Y2M10DT2H30M or P1M", now()),
+			"1 year, 2 months, 10 days, 2 hours and 30 minutes or 1 month"
+		);
+	}
+
+	#[test]
+	fn filter_keeps_short_durations_and_words() {
+		let filter = settings(&["--filter"], "");
+		assert_eq!(filter.filter("12ms, 5 APPs", now()), "12ms, 5 APPs");
+		assert!(matches!(
+			filter.filter("nothing to see", now()),
+			Cow::Borrowed(_)
+		));
+	}
+
+	#[test]
+	fn filter_patterns() {
+		let file = "patterns = ['after (?<value>\\d+) (?<unit>seconds)']";
+		let filter = settings(&["--filter", "--style", "compact"], file);
+		assert_eq!(
+			filter.filter("done after 90 seconds, not 90s", now()),
+			"done after 1m 30s, not 90s"
+		);
+	}
+
+	#[test]
+	fn filter_patterns_need_groups() {
+		assert!(toml::from_str::<FileSettings>("patterns = ['(\\d+)s']").is_err());
+		assert!(Args::try_parse_from(["stringify-interval", "--pattern", "(?<iso>P.+)"]).is_err());
+	}
+
+	#[test]
+	fn filter_reads_only_plain_counts() {
+		let file = "patterns = ['<(?<value>[^>]*) (?<unit>s)>']";
+		let filter = settings(&["--filter"], file);
+		assert_eq!(
+			filter.filter("<in 5 s> <1, 2 s> <1,5 s>", now()),
+			"<in 5 s> <1, 2 s> <1 second>"
+		);
+	}
+}
@@ -0,0 +1,416 @@
+use alloc::boxed::Box;
+
+use chrono::{
+	DateTime, Datelike, Days, Duration, LocalResult, Months, NaiveDateTime, NaiveTime, Offset,
+	TimeZone, Utc,
+};
+
+use crate::countdown::Horizon;
+use crate::options::{Anchor, DisplayConfigRef, OptionalDisplaySettings};
+use crate::util::{Unit, UnitValues};
+
+/// A time zone that calendar units are counted in, with its type erased.
+pub(crate) trait Zone {
+	/// The local time at the given moment, if it can be represented.
+	fn local(&self, date: DateTime<Utc>) -> Option<NaiveDateTime>;
+	/// The moment at the given local time. A local time that happens twice is the earlier one, and one that is skipped by a transition is moved past it.
+	fn moment(&self, local: NaiveDateTime) -> Option<DateTime<Utc>>;
+}
+
+impl<Tz: TimeZone> Zone for Tz {
+	fn local(&self, date: DateTime<Utc>) -> Option<NaiveDateTime> {
+		let offset = self.offset_from_utc_datetime(&date.naive_utc()).fix();
+		date.naive_utc()
+			.checked_add_signed(Duration::seconds(offset.local_minus_utc().into()))
+	}
+	fn moment(&self, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+		let offset = match self.offset_from_local_datetime(&local) {
+			LocalResult::Single(offset) | LocalResult::Ambiguous(offset, _) => offset.fix(),
+			// With the offset from before the transition, the skipped time lands as far past it.
+			LocalResult::None => self
+				.offset_from_local_datetime(&local.checked_sub_signed(Duration::days(1))?)
+				.earliest()?
+				.fix(),
+		};
+		Some(
+			local
+				.checked_sub_signed(Duration::seconds(offset.local_minus_utc().into()))?
+				.and_utc(),
+		)
+	}
+}
+
+/// The date calendar units are counted with, and the time zone they are counted in.
+pub(crate) struct ReferenceDate {
+	pub(crate) date: DateTime<Utc>,
+	pub(crate) zone: Box<dyn Zone>,
+}
+
+impl ReferenceDate {
+	pub(crate) fn new<Tz: TimeZone + 'static>(date: DateTime<Tz>) -> Self {
+		Self {
+			date: date.with_timezone(&Utc),
+			zone: Box::new(date.timezone()),
+		}
+	}
+}
+
+/// The calendar units taken out of an interval, and what is left of it.
+pub(crate) struct CalendarSplit {
+	/// Which of the month-based calendar units are displayed.
+	pub(crate) enabled: UnitValues<bool>,
+	/// The months taken out, a whole number of the smallest displayed month-based unit.
+	pub(crate) months: u32,
+	/// The calendar days taken out after the months, a whole number of the smallest displayed unit counted in days.
+	pub(crate) days: u64,
+	pub(crate) remainder: Duration,
+	/// The time from the end of the units taken out to one more of the smallest calendar unit, if there is one and that date can be represented.
+	to_next: Option<Duration>,
+	/// The interval up to one more of the smallest calendar unit.
+	next_interval: Option<Duration>,
+	pub(crate) edges: CalendarEdges,
+}
+
+/// Where what the calendar takes out of the interval changes as it counts down, for `Horizon`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CalendarEdges {
+	target: DateTime<Utc>,
+	/// The nearest date from the target towards the start where the months or days counted change, and the nearest one past the target.
+	before: DateTime<Utc>,
+	after: Option<DateTime<Utc>>,
+	/// The end of the units taken out, and one more of the smallest of them.
+	end: DateTime<Utc>,
+	next: Option<DateTime<Utc>>,
+	remainder: u64,
+	/// When the interval is rounded to the nearest of the smallest calendar unit, the remainder from which one more of it is nearer.
+	round_at: Option<u64>,
+}
+
+impl CalendarSplit {
+	/// Whether one more of the smallest calendar unit is at least as close to the remainder as rounding it to `rounded` seconds. Limits the horizon to where that changes.
+	pub(crate) fn next_is_closer(&self, remainder: u64, rounded: u64, horizon: &mut Horizon) -> bool {
+		let Some(to_next) = self
+			.to_next
+			.and_then(|to_next| u64::try_from(to_next.num_seconds()).ok())
+		else {
+			return false;
+		};
+		// The remainder is less than `to_next`, so when it is over `rounded`, the two are equally close halfway between.
+		horizon.edge(remainder, to_next.saturating_add(rounded).div_ceil(2));
+		to_next.abs_diff(remainder) <= rounded.abs_diff(remainder)
+	}
+	/// The split up to one more of the smallest calendar unit, which may carry into the larger ones. The same units are displayed.
+	pub(crate) fn carried(&self, calendar: &Calendar) -> Option<Self> {
+		calendar.split_with(self.next_interval?, false, Some(self.enabled))
+	}
+}
+
+pub(crate) const MONTHS_PER: UnitValues<u32> = UnitValues {
+	centuries: 100 * 12,
+	decades: 10 * 12,
+	years: 12,
+	quarters: 3,
+	months: 1,
+	weeks: 0,
+	days: 0,
+	hours: 0,
+	minutes: 0,
+	seconds: 0,
+};
+
+/// Counts calendar units from a start date, in the reference date's time zone.
+pub(crate) struct Calendar<'l> {
+	start: DateTime<Utc>,
+	/// The local time at `start`, which the units are counted from so that they keep the time of day.
+	start_local: NaiveDateTime,
+	backwards: bool,
+	zone: &'l dyn Zone,
+	config: DisplayConfigRef<'l>,
+	/// The days in the smallest displayed unit that is counted in calendar days, or 0 if there is none.
+	day_step: u64,
+	/// Whether the start is the reference date, which moves along with the clock as the interval counts down.
+	moving: bool,
+}
+
+impl<'l> Calendar<'l> {
+	/// Counts from the end of the absolute `interval` chosen by the anchor, forwards or backwards towards the other end.
+	pub(crate) fn new(
+		reference: &'l ReferenceDate,
+		interval: Duration,
+		in_past: bool,
+		config: DisplayConfigRef<'l>,
+		day_step: u64,
+	) -> Option<Self> {
+		let date = reference.date;
+		let (start, backwards, moving) = match (config.anchor, in_past) {
+			(Anchor::ReferenceDate, _) | (Anchor::Start, false) | (Anchor::End, true) => {
+				(date, in_past, true)
+			}
+			(Anchor::Start, true) => (date.checked_sub_signed(interval)?, false, false),
+			(Anchor::End, false) => (date.checked_add_signed(interval)?, true, false),
+		};
+		Some(Self {
+			start,
+			start_local: reference.zone.local(start)?,
+			backwards,
+			zone: reference.zone.as_ref(),
+			config,
+			day_step,
+			moving,
+		})
+	}
+	/// Takes the calendar units out of the absolute `interval`. If `should_round`, nothing is left, since the interval is rounded to the nearest of the smallest calendar unit.
+	pub(crate) fn split(&self, interval: Duration, should_round: bool) -> Option<CalendarSplit> {
+		self.split_with(interval, should_round, None)
+	}
+	/// Like `split`, but with the month-based units to display already chosen, if `enabled` is given.
+	fn split_with(
+		&self,
+		interval: Duration,
+		should_round: bool,
+		enabled: Option<UnitValues<bool>>,
+	) -> Option<CalendarSplit> {
+		let total_seconds = interval.num_seconds().unsigned_abs();
+		let target = self.further(interval)?;
+		let target_local = self.zone.local(target)?;
+		let passes_target = |date: DateTime<Utc>| {
+			if self.backwards {
+				date < target
+			} else {
+				date > target
+			}
+		};
+
+		let (larger, smaller) = if self.backwards {
+			(self.start_local, target_local)
+		} else {
+			(target_local, self.start_local)
+		};
+		let mut months: u32 = ((larger.year() - smaller.year()) * 12 + larger.month() as i32
+			- smaller.month() as i32)
+			.try_into()
+			.unwrap_or(0);
+		// The guess can be off by a month or two when the start date's day doesn't exist in a month, or when the time of day hasn't been reached yet.
+		while passes_target(self.months_further(months)?) {
+			months = months.checked_sub(1)?;
+		}
+		while months
+			.checked_add(1)
+			.and_then(|months| self.months_further(months))
+			.is_some_and(|date| !passes_target(date))
+		{
+			months += 1;
+		}
+		let months_edges = (
+			self.months_further(months)?,
+			months
+				.checked_add(1)
+				.and_then(|months| self.months_further(months)),
+		);
+
+		let enabled = enabled.unwrap_or_else(|| {
+			let mut enabled = UnitValues::<bool>::default();
+			for ((enabled, settings), &months_per) in enabled
+				.iter_mut()
+				.zip(self.config.iter())
+				.zip(MONTHS_PER.iter())
+				.take(Unit::CALENDAR_COUNT)
+			{
+				*enabled = settings.range_contains(months / months_per, total_seconds);
+			}
+			enabled
+		});
+		let step = enabled
+			.iter()
+			.zip(MONTHS_PER.iter())
+			.take(Unit::CALENDAR_COUNT)
+			.filter_map(|(&enabled, &months_per)| enabled.then_some(months_per))
+			.next_back()
+			.unwrap_or(0);
+		let months = months.checked_rem(step).map_or(0, |rest| months - rest);
+		let months_date = self.months_further(months)?;
+		let months_local =
+			self.config
+				.month_arithmetic
+				.shift(self.start_local, months, self.backwards)?;
+
+		let (days, end, next, days_edges) = if self.day_step > 0 {
+			let mut days = target_local
+				.date()
+				.signed_duration_since(months_local.date())
+				.num_days()
+				.unsigned_abs();
+			while passes_target(self.days_further(months_local, months_date, days)?) {
+				days = days.checked_sub(1)?;
+			}
+			while days
+				.checked_add(1)
+				.and_then(|days| self.days_further(months_local, months_date, days))
+				.is_some_and(|date| !passes_target(date))
+			{
+				days += 1;
+			}
+			let days_edges = (
+				self.days_further(months_local, months_date, days)?,
+				days.checked_add(1)
+					.and_then(|days| self.days_further(months_local, months_date, days)),
+			);
+			let days = days - days % self.day_step;
+			let next = days
+				.checked_add(self.day_step)
+				.and_then(|days| self.days_further(months_local, months_date, days));
+			(
+				days,
+				self.days_further(months_local, months_date, days)?,
+				next,
+				Some(days_edges),
+			)
+		} else {
+			let next = (step > 0)
+				.then(|| months.checked_add(step))
+				.flatten()
+				.and_then(|months| self.months_further(months));
+			(0, months_date, next, None)
+		};
+
+		let nearest = |dates: [Option<DateTime<Utc>>; 2]| {
+			dates
+				.into_iter()
+				.flatten()
+				.min_by_key(|date| (target - *date).abs())
+		};
+		let remainder = (target - end).num_seconds().unsigned_abs();
+		let edges = CalendarEdges {
+			target,
+			before: nearest([Some(months_edges.0), days_edges.map(|edges| edges.0)])?,
+			after: nearest([months_edges.1, days_edges.and_then(|edges| edges.1)]),
+			end,
+			next,
+			remainder,
+			round_at: next
+				.filter(|_| should_round)
+				.map(|next| (next - end).num_seconds().unsigned_abs().div_ceil(2)),
+		};
+
+		if should_round {
+			if let Some(next) = next {
+				if (target - next).abs() <= (target - end).abs() {
+					// One more of the smallest calendar unit is closer, and it may carry into the larger ones.
+					return self
+						.split_with((next - self.start).abs(), false, Some(enabled))
+						.map(|split| CalendarSplit {
+							remainder: Duration::zero(),
+							edges,
+							..split
+						});
+				}
+			}
+		}
+		Some(CalendarSplit {
+			enabled,
+			months,
+			days,
+			remainder: if should_round {
+				Duration::zero()
+			} else {
+				(target - end).abs()
+			},
+			to_next: next.map(|next| (next - end).abs()),
+			next_interval: next.map(|next| (next - self.start).abs()),
+			edges,
+		})
+	}
+	/// Limits the horizon to when the target passes the dates where the months or days counted change, or the remainder reaches where rounding goes the other way. When the start moves along with the clock, the dates move with it, but only as long as the months from it keep their lengths.
+	pub(crate) fn limit_horizon(&self, edges: &CalendarEdges, horizon: &mut Horizon) {
+		let seconds = |duration: Duration| duration.num_seconds().unsigned_abs();
+		let before = seconds(edges.target - edges.before);
+		horizon.edge(before, 0);
+		if let Some(after) = edges.after {
+			horizon.edge(before, before.saturating_add(seconds(after - edges.target)));
+		}
+		if let Some(round_at) = edges.round_at {
+			horizon.edge(edges.remainder, round_at);
+		}
+		if self.moving {
+			let dates = [Some(edges.before), edges.after, Some(edges.end), edges.next];
+			// Without those lengths, only the next second is known to be the same.
+			horizon.limit(
+				self.seconds_keeping_lengths(dates.into_iter().flatten())
+					.unwrap_or(0),
+			);
+		}
+	}
+	/// How many whole seconds the start can move forward while the months and days from it keep their lengths, so that the dates counted from it move along the same.
+	///
+	/// That is until its day of the month is past the 28th, which not every month has, or it is in the next month, or until the offset of the time zone changes at the start or at any of `dates`.
+	fn seconds_keeping_lengths(&self, dates: impl Iterator<Item = DateTime<Utc>>) -> Option<u64> {
+		let day = self.start_local.date();
+		let next_month = day.with_day(1)?.checked_add_months(Months::new(1))?;
+		let until = match day.day() {
+			..=28 => day.with_day(29).unwrap_or(next_month),
+			_ => day.succ_opt()?,
+		};
+		let mut seconds = self
+			.zone
+			.moment(until.and_time(NaiveTime::MIN))?
+			.signed_duration_since(self.start)
+			.num_seconds();
+		let offset = |date: DateTime<Utc>| Some(self.zone.local(date)? - date.naive_utc());
+		for date in core::iter::once(self.start).chain(dates) {
+			let at = offset(date)?;
+			let changed = |seconds: i64| {
+				Some(offset(date.checked_add_signed(Duration::try_seconds(seconds)?)?)? != at)
+			};
+			if changed(seconds)? {
+				// The offset changes at most once in that time, so the second it does is found by halving.
+				let mut same = 0;
+				while seconds - same > 1 {
+					let middle = same + (seconds - same) / 2;
+					if changed(middle)? {
+						seconds = middle;
+					} else {
+						same = middle;
+					}
+				}
+				seconds = same;
+			}
+		}
+		u64::try_from(seconds).ok()
+	}
+	fn further(&self, interval: Duration) -> Option<DateTime<Utc>> {
+		if self.backwards {
+			self.start.checked_sub_signed(interval)
+		} else {
+			self.start.checked_add_signed(interval)
+		}
+	}
+	/// Always counted from the start date, so that adjusting for a shorter month doesn't carry over.
+	fn months_further(&self, months: u32) -> Option<DateTime<Utc>> {
+		if months == 0 {
+			// The local time may happen twice, so it doesn't always lead back to the start.
+			return Some(self.start);
+		}
+		let local = self
+			.config
+			.month_arithmetic
+			.shift(self.start_local, months, self.backwards)?;
+		self.zone.moment(local)
+	}
+	/// The moment `days` calendar days further than the local time `from`, which is at the moment `from_date`.
+	fn days_further(
+		&self,
+		from: NaiveDateTime,
+		from_date: DateTime<Utc>,
+		days: u64,
+	) -> Option<DateTime<Utc>> {
+		if days == 0 {
+			return Some(from_date);
+		}
+		let local = if self.backwards {
+			from.checked_sub_days(Days::new(days))?
+		} else {
+			from.checked_add_days(Days::new(days))?
+		};
+		self.zone.moment(local)
+	}
+}
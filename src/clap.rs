@@ -0,0 +1,136 @@
+//! A `clap` value parser for duration arguments, like `--timeout "2 hours and 5 minutes"` or `--timeout 2h5m`.
+//!
+//! ```
+//! # use clap::{Arg, Command};
+//! # use stringify_interval::clap::DurationParser;
+//! let command = Command::new("server").arg(
+//!     Arg::new("timeout")
+//!         .long("timeout")
+//!         .value_parser(DurationParser::<std::time::Duration>::default()),
+//! );
+//! let matches = command.clone().try_get_matches_from(["server", "--timeout", "1m30s"]).unwrap();
+//! assert_eq!(
+//!     matches.get_one::<std::time::Duration>("timeout"),
+//!     Some(&std::time::Duration::from_secs(90)),
+//! );
+//!
+//! let error = command.try_get_matches_from(["server", "--timeout", "5 fortnights"]).unwrap_err();
+//! assert!(error.to_string().contains("Unknown unit \"fortnights\", expected one of: weeks, week,"));
+//! ```
+
+use std::error::Error;
+use std::ffi::OsStr;
+use std::marker::PhantomData;
+
+use ::clap::builder::TypedValueParser;
+use ::clap::error::ErrorKind;
+use ::clap::{Arg, Command};
+use chrono::Duration;
+
+use crate::errors::ParseError;
+use crate::options::Text;
+
+/// A duration type that `DurationParser` can produce.
+pub trait ParsedDuration: Sized + Clone + Send + Sync + 'static {
+	/// Converts a parsed duration, or says why it can't be.
+	fn from_duration(interval: Duration) -> Result<Self, String>;
+}
+
+impl ParsedDuration for Duration {
+	fn from_duration(interval: Duration) -> Result<Self, String> {
+		Ok(interval)
+	}
+}
+
+impl ParsedDuration for std::time::Duration {
+	fn from_duration(interval: Duration) -> Result<Self, String> {
+		interval
+			.to_std()
+			.map_err(|_| String::from("The duration must not be negative"))
+	}
+}
+
+/// Parses duration arguments with `parse`, into a `chrono::Duration` or a `std::time::Duration`.
+///
+/// Each `Text` is tried in turn. By default, those are `Text::default` and `Text::compact`, for both "2 hours and 5 minutes" and "2h5m". When no unit is recognised, the error lists the labels of all of them.
+#[derive(Debug, Clone)]
+pub struct DurationParser<T = Duration> {
+	texts: Vec<Text>,
+	output: PhantomData<fn() -> T>,
+}
+
+impl<T> DurationParser<T> {
+	/// A parser that tries each of `texts` in turn.
+	pub fn new(texts: impl IntoIterator<Item = Text>) -> Self {
+		Self {
+			texts: texts.into_iter().collect(),
+			output: PhantomData,
+		}
+	}
+}
+
+impl<T> Default for DurationParser<T> {
+	fn default() -> Self {
+		Self::new([Text::default(), Text::compact()])
+	}
+}
+
+impl<T: ParsedDuration> DurationParser<T> {
+	fn parse(&self, input: &str) -> Result<T, Box<dyn Error + Send + Sync>> {
+		let mut first_error = None;
+		let mut units: Vec<String> = Vec::new();
+		for text in &self.texts {
+			match crate::parse(input, text) {
+				Ok(interval) => return Ok(T::from_duration(interval)?),
+				Err(ParseError::UnknownUnit {
+					found,
+					units: text_units,
+				}) => {
+					for unit in text_units {
+						if !units.contains(&unit) {
+							units.push(unit);
+						}
+					}
+					first_error.get_or_insert(ParseError::UnknownUnit {
+						found,
+						units: Vec::new(),
+					});
+				}
+				Err(error) => {
+					first_error.get_or_insert(error);
+				}
+			}
+		}
+		Err(match first_error {
+			Some(ParseError::UnknownUnit { found, .. }) => {
+				ParseError::UnknownUnit { found, units }.into()
+			}
+			Some(error) => error.into(),
+			None => "There were no texts to parse the duration with".into(),
+		})
+	}
+}
+
+impl<T: ParsedDuration> TypedValueParser for DurationParser<T> {
+	type Value = T;
+
+	fn parse_ref(
+		&self,
+		cmd: &Command,
+		arg: Option<&Arg>,
+		value: &OsStr,
+	) -> Result<Self::Value, ::clap::Error> {
+		let input = value
+			.to_str()
+			.ok_or_else(|| ::clap::Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd))?;
+		self.parse(input).map_err(|error| {
+			// Worded like clap's own invalid values, which it only builds for its parsers.
+			let arg = arg.map_or_else(|| String::from("..."), |arg| arg.to_string());
+			::clap::Error::raw(
+				ErrorKind::ValueValidation,
+				format!("invalid value '{input}' for '{arg}': {error}\n"),
+			)
+			.with_cmd(cmd)
+		})
+	}
+}
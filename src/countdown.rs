@@ -0,0 +1,99 @@
+use alloc::string::String;
+
+use chrono::Duration;
+
+use crate::errors::StringifyError;
+
+/// The text for an interval that counts down as time passes, like the time left until a deadline, or the time since something as a negative interval, and how long the text stays the same.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Countdown {
+	pub text: String,
+	/// The time until the text is first different, or `None` if it never is before the interval or the reference date go out of range.
+	pub next_change: Option<Duration>,
+}
+
+/// How many times the whole seconds of the interval can count down before the text may change, found while stringifying it.
+///
+/// Until then, everything the text is worked out from moves by one second each time, in the direction the absolute interval moves: the interval itself, what is left after the calendar units, and the rest after each larger unit. Each place where the text depends on one of those passing some value limits the horizon to when it does.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Horizon {
+	/// Whether the absolute interval gets shorter as it counts down, which it does until it turns negative.
+	shrinking: bool,
+	steps: u64,
+}
+
+impl Horizon {
+	/// For an interval of `seconds` whole seconds. A positive interval is in the past one step after it reaches zero.
+	pub(crate) fn new(in_past: bool, seconds: u64) -> Self {
+		Self {
+			shrinking: !in_past,
+			steps: if in_past {
+				u64::MAX
+			} else {
+				seconds.saturating_add(1)
+			},
+		}
+	}
+	/// Limits the horizon to when `value` first moves to the other side of `edge`, where the upper side includes `edge`.
+	pub(crate) fn edge(&mut self, value: u64, edge: u64) {
+		let steps = if self.shrinking {
+			value.checked_sub(edge).map(|steps| steps.saturating_add(1))
+		} else {
+			edge.checked_sub(value).filter(|&steps| steps > 0)
+		};
+		if let Some(steps) = steps {
+			self.limit(steps);
+		}
+	}
+	/// Limits the horizon to the given number of steps, or one step if it is 0.
+	pub(crate) fn limit(&mut self, steps: u64) {
+		self.steps = self.steps.min(steps.max(1));
+	}
+	pub(crate) fn steps(&self) -> u64 {
+		self.steps
+	}
+}
+
+/// The time until `stringify` gives something other than `current`, when the text may first change after `steps` whole seconds. `stringify` gives the text, and how many more whole seconds it may stay the same, for the interval after the given time has passed.
+///
+/// It is only called at the moments the interval reaches another whole second, since the text only changes then, and only where the text may change. Just under zero counts as another second, since that is in the past.
+pub(crate) fn next_change<F>(
+	interval: Duration,
+	current: &str,
+	steps: u64,
+	stringify: F,
+) -> Option<Duration>
+where
+	F: Fn(Duration) -> Option<Result<(String, u64), StringifyError>>,
+{
+	let seconds = interval.num_seconds();
+	// The whole seconds from 0 up are the interval rounded towards zero, and those from -1 down are one less, since just under zero is -1.
+	let position = if interval < Duration::zero() {
+		seconds.checked_sub(1)?
+	} else {
+		seconds
+	};
+	// The time until the interval first reaches the position `passed` further down.
+	let elapsed = |passed: u64| {
+		let position = position.checked_sub_unsigned(passed)?;
+		let remaining = match position {
+			// Just below the next whole second up, since the seconds are rounded towards zero.
+			0.. => Duration::try_seconds(position.checked_add(1)?)?
+				.checked_sub(&Duration::nanoseconds(1))?,
+			-1 => Duration::nanoseconds(-1),
+			_ => Duration::try_seconds(position + 1)?,
+		};
+		interval.checked_sub(&remaining)
+	};
+
+	let mut passed = 0_u64;
+	let mut steps = steps;
+	loop {
+		passed = passed.checked_add(steps)?;
+		let elapsed = elapsed(passed)?;
+		match stringify(elapsed)? {
+			Ok((text, more_steps)) if text == current => steps = more_steps,
+			_ => return Some(elapsed),
+		}
+	}
+}
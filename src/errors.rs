@@ -1,7 +1,131 @@
-#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::util::Unit;
+
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum StringifyError {
-	#[error("Some operation overflowed or some number conversion failed")]
-	NumberOutOfRange,
+	#[error(
+		"Some operation overflowed or some number conversion failed{}, for interval {interval}{}",
+		unit.as_ref().map(|unit| format!(" in {unit}")).unwrap_or_default(),
+		date.map(|date| format!(" from {date}")).unwrap_or_default(),
+	)]
+	NumberOutOfRange {
+		/// The unit being calculated, if the problem was with a specific one.
+		unit: Option<Unit>,
+		interval: Duration,
+		/// The reference date, if one was used.
+		date: Option<DateTime<Utc>>,
+	},
 	#[error("No units were enabled")]
 	NoUnitsEnabled,
+	#[error("Calendar units like years or months were enabled, but there was no reference date")]
+	MissingReferenceDate,
+	#[error("The interval {interval} could not be split exactly across the units, which is a bug")]
+	InternalRounding { interval: Duration },
+	#[error("The {unit} were displayed without display settings, which is a bug")]
+	InternalMissingSettings { unit: Unit },
+	#[error("The text for {unit} is empty")]
+	EmptyLabel { unit: Unit },
+	/// The text for a unit has `{n}` more than once, but there is only one count to put in it.
+	#[error("The text for {unit} has {{n}} more than once")]
+	RepeatedCount { unit: Unit },
+	/// The range doesn't contain any number, because it doesn't end after it starts. `upper` is exclusive.
+	#[error(
+		"The display range for {unit} starts at {lower}, but ends before {upper}, so it is empty"
+	)]
+	InvertedRange { unit: Unit, lower: u64, upper: u64 },
+	/// A built-in unit from weeks to seconds has display settings, but there is a list of constant units, which replaces them.
+	#[error("The {unit} have display settings, but the list of constant units replaces them")]
+	ReplacedUnit { unit: Unit },
+	#[error("The length of {unit} is {length}, but it must be at least one second")]
+	InvalidLength { unit: Unit, length: Duration },
+	/// The writer given to `write_without_date` returned an error.
+	#[error("The text couldn't be written")]
+	Write,
+}
+
+/// Why some text couldn't be parsed into an interval with `parse`.
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseError {
+	#[error("There was no interval to parse")]
+	Empty,
+	#[error("Expected a number, but found \"{found}\"")]
+	ExpectedNumber { found: String },
+	#[error("Expected a unit after \"{number}\"")]
+	MissingUnit { number: String },
+	#[error("Unknown unit \"{found}\", expected one of: {}", units.join(", "))]
+	UnknownUnit {
+		found: String,
+		/// The labels of the units that can be parsed.
+		units: Vec<String>,
+	},
+	#[error("The interval is too long")]
+	OutOfRange,
+}
+
+/// The kind of a `StringifyError`, without the context, for matching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+	NumberOutOfRange,
+	NoUnitsEnabled,
+	MissingReferenceDate,
+	InternalRounding,
+	InternalMissingSettings,
+	EmptyLabel,
+	RepeatedCount,
+	InvertedRange,
+	ReplacedUnit,
+	InvalidLength,
+	Write,
+}
+
+impl StringifyError {
+	pub fn kind(&self) -> ErrorKind {
+		match self {
+			Self::NumberOutOfRange { .. } => ErrorKind::NumberOutOfRange,
+			Self::NoUnitsEnabled => ErrorKind::NoUnitsEnabled,
+			Self::MissingReferenceDate => ErrorKind::MissingReferenceDate,
+			Self::InternalRounding { .. } => ErrorKind::InternalRounding,
+			Self::InternalMissingSettings { .. } => ErrorKind::InternalMissingSettings,
+			Self::EmptyLabel { .. } => ErrorKind::EmptyLabel,
+			Self::RepeatedCount { .. } => ErrorKind::RepeatedCount,
+			Self::InvertedRange { .. } => ErrorKind::InvertedRange,
+			Self::ReplacedUnit { .. } => ErrorKind::ReplacedUnit,
+			Self::InvalidLength { .. } => ErrorKind::InvalidLength,
+			Self::Write => ErrorKind::Write,
+		}
+	}
+	/// An overflow while working on the given unit, if any. The interval and date are filled in by `with_context`.
+	pub(crate) fn out_of_range(unit: Option<Unit>) -> Self {
+		Self::NumberOutOfRange {
+			unit,
+			interval: Duration::zero(),
+			date: None,
+		}
+	}
+	/// Fills in the interval and date the error happened with.
+	pub(crate) fn with_context(mut self, interval: Duration, date: Option<DateTime<Utc>>) -> Self {
+		match &mut self {
+			Self::NumberOutOfRange {
+				interval: error_interval,
+				date: error_date,
+				..
+			} => {
+				*error_interval = interval;
+				*error_date = date;
+			}
+			Self::InternalRounding {
+				interval: error_interval,
+			} => *error_interval = interval,
+			_ => (),
+		}
+		self
+	}
 }
@@ -1,33 +1,72 @@
-//! stringify_interval generates a user-friendly string from a `chrono::Duration`, like "1 day, 5 hours and 20 minutes". Years and months can be displayed, but they will need some date as a reference point, because the exact length of a year or month can vary.
+//! stringify_interval generates a user-friendly string from a `chrono::Duration`, like "1 day, 5 hours and 20 minutes". Years and months can be displayed, as well as centuries, decades and quarters, but they will need some date as a reference point, because the exact length of a year or month can vary.
 //!
 //! It can be configured to show different units depending on the size of the interval, and to customize the strings used to compose the output.
 //! 
 //! ```
 //! # use stringify_interval::{DisplayConfigConstant, Text};
 //! let text = stringify_interval::without_date(
-//! 	chrono::Duration::seconds(1_234_567),
-//! 	&DisplayConfigConstant::default(),
-//! 	&Text::default(),
+//!     chrono::Duration::seconds(1_234_567),
+//!     &DisplayConfigConstant::default(),
+//!     &Text::default(),
 //! );
 //! assert_eq!(text, Ok(String::from("14 days, 6 hours and 56 minutes")));
 //!```
 
-use chrono::{DateTime, Duration, Utc};
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+extern crate alloc;
+
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use chrono::Utc;
+use chrono::{DateTime, Duration, TimeZone};
+
+use calendar::ReferenceDate;
 use errors::StringifyError;
-use stringify::stringify_interval;
+use stringify::{
+	stringify_business_time, stringify_interval, stringify_with_horizon, write_interval, Writer,
+};
 
+mod calendar;
+#[cfg(feature = "clap")]
+pub mod clap;
+mod countdown;
 pub mod errors;
+mod number_format;
 mod options;
+mod parse;
+mod relative;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "tokio")]
+mod stream;
 mod stringify;
 mod tests;
 mod threshold_map;
 mod util;
+#[cfg(feature = "wasm-bindgen")]
+mod wasm;
+mod work_calendar;
 
-pub use options::{DisplayConfig, DisplayConfigConstant, DisplayRange, DisplaySettings, Text};
+pub use countdown::Countdown;
+pub use number_format::NumberFormat;
+pub use options::{
+	Anchor, ConstantUnit, ContextText, DisplayConfig, DisplayConfigConstant, DisplayRange,
+	DisplaySettings, MonthArithmetic, Phrasing, Text, UnitLabels,
+};
+pub use parse::parse;
+pub use relative::RelativeText;
+#[cfg(feature = "tokio")]
+pub use stream::IntervalStream;
 pub use threshold_map::ThresholdMap;
+pub use util::Unit;
+pub use work_calendar::{WorkCalendar, WorkWeek};
 
-/// Stringify an interval with a configurable format. Years and months cannot be included.
+/// Stringify an interval with a configurable format. Calendar units like years and months cannot be included.
 /// 
 /// The default looks like "14 days, 6 hours and 56 minutes".
 pub fn without_date(
@@ -38,36 +77,58 @@ pub fn without_date(
 	stringify_interval(interval, None, config.into(), text)
 }
 
-/// Stringify an interval with a configurable format. Years and months can be included, and they will be calculated with the given date as a reference point.
+/// Stringify an interval like `without_date`, but write the text into `output`, like a `core::fmt::Formatter` or a fixed-size buffer, instead of returning it. If writing fails, some of the text may have been written already.
+pub fn write_without_date<W: fmt::Write + ?Sized>(
+	output: &mut W,
+	interval: Duration,
+	config: &DisplayConfigConstant,
+	text: &Text,
+) -> Result<(), StringifyError> {
+	write_interval(interval, None, config.into(), text, &mut Writer(output)).map(|_| ())
+}
+
+/// Stringify an interval with a configurable format. Years and months can be included, and they will be calculated with the given date as a reference point, in its time zone.
 /// 
 /// The default looks like "14 days, 6 hours and 56 minutes".
-pub fn with_date(
+pub fn with_date<Tz: TimeZone + 'static>(
 	interval: Duration,
-	date: DateTime<Utc>,
+	date: DateTime<Tz>,
 	config: &DisplayConfig,
 	text: &Text,
 ) -> Result<String, StringifyError> {
-	stringify_interval(interval, Some(Box::new(move || date)), config.into(), text)
+	stringify_interval(
+		interval,
+		Some(Box::new(move || ReferenceDate::new(date))),
+		config.into(),
+		text,
+	)
 }
 
-/// Stringify an interval with a configurable format. Years and months can be included, and they will be calculated with the date yielded by the given closure as a reference point.
+/// Stringify an interval with a configurable format. Years and months can be included, and they will be calculated with the date yielded by the given closure as a reference point, in its time zone.
 /// 
 /// The default looks like "14 days, 6 hours and 56 minutes".
-pub fn with_lazy_date<D>(
+pub fn with_lazy_date<Tz, D>(
 	interval: Duration,
 	get_date: D,
 	config: &DisplayConfig,
 	text: &Text,
 ) -> Result<String, StringifyError>
 where
-	D: FnOnce() -> DateTime<Utc> + 'static,
+	Tz: TimeZone + 'static,
+	D: FnOnce() -> DateTime<Tz> + 'static,
 {
-	stringify_interval(interval, Some(Box::new(get_date)), config.into(), text)
+	stringify_interval(
+		interval,
+		Some(Box::new(move || ReferenceDate::new(get_date()))),
+		config.into(),
+		text,
+	)
 }
 
 /// Stringify an interval with a configurable format. Years and months can be included, and they will be calculated with the current system time as a reference point.
 /// 
 /// The default looks like "14 days, 6 hours and 56 minutes".
+#[cfg(feature = "std")]
 pub fn with_now(
 	interval: Duration,
 	config: &DisplayConfig,
@@ -75,3 +136,89 @@ pub fn with_now(
 ) -> Result<String, StringifyError> {
 	with_lazy_date(interval, Utc::now, config, text)
 }
+
+/// Stringify an interval like `without_date`, and find how long until the text changes as the interval counts down with time.
+pub fn countdown_without_date(
+	interval: Duration,
+	config: &DisplayConfigConstant,
+	text: &Text,
+) -> Result<Countdown, StringifyError> {
+	let (current, steps) = stringify_with_horizon(interval, None, config.into(), text)?;
+	let next_change = countdown::next_change(interval, &current, steps, |elapsed| {
+		Some(stringify_with_horizon(
+			interval.checked_sub(&elapsed)?,
+			None,
+			config.into(),
+			text,
+		))
+	});
+	Ok(Countdown {
+		text: current,
+		next_change,
+	})
+}
+
+/// Stringify an interval like `with_date`, and find how long until the text changes as the interval counts down with time. The reference date moves forward by as much as the interval counts down, so calendar units keep counting towards the same date.
+pub fn countdown_with_date<Tz: TimeZone + 'static>(
+	interval: Duration,
+	date: DateTime<Tz>,
+	config: &DisplayConfig,
+	text: &Text,
+) -> Result<Countdown, StringifyError> {
+	let reference = |date: DateTime<Tz>| -> Option<Box<dyn FnOnce() -> ReferenceDate>> {
+		Some(Box::new(move || ReferenceDate::new(date)))
+	};
+	let (current, steps) =
+		stringify_with_horizon(interval, reference(date.clone()), config.into(), text)?;
+	let next_change = countdown::next_change(interval, &current, steps, |elapsed| {
+		Some(stringify_with_horizon(
+			interval.checked_sub(&elapsed)?,
+			reference(date.clone().checked_add_signed(elapsed)?),
+			config.into(),
+			text,
+		))
+	});
+	Ok(Countdown {
+		text: current,
+		next_change,
+	})
+}
+
+/// Stringify an interval from the given date as a word like "yesterday" or "next month", if it leads to the calendar day, week, month or year right before or after the date's, in its time zone. Otherwise, it is stringified the same as with `with_date`.
+pub fn relative_date<Tz: TimeZone + 'static>(
+	interval: Duration,
+	date: DateTime<Tz>,
+	config: &DisplayConfig,
+	text: &Text,
+	words: &RelativeText,
+) -> Result<String, StringifyError> {
+	let word = date
+		.clone()
+		.checked_add_signed(interval)
+		.and_then(|target| words.word(date.date_naive(), target.date_naive()));
+	match word {
+		Some(word) => Ok(word.to_owned()),
+		None => with_date(interval, date, config, text),
+	}
+}
+
+/// Stringify the working time in an interval from the given date with a configurable format, like "3 days and 2 hours" for an interval from a Friday afternoon to the Wednesday after. Working time is counted in the date's time zone, and days and weeks are business days and weeks, as long as the `WorkCalendar` makes them. Intervals of more than 100 years are out of range, since the days are counted one by one.
+pub fn business_time<Tz, W>(
+	interval: Duration,
+	date: DateTime<Tz>,
+	calendar: &W,
+	config: &DisplayConfigConstant,
+	text: &Text,
+) -> Result<String, StringifyError>
+where
+	Tz: TimeZone + 'static,
+	W: WorkCalendar,
+{
+	stringify_business_time(
+		interval,
+		ReferenceDate::new(date),
+		calendar,
+		config.into(),
+		text,
+	)
+}
@@ -0,0 +1,138 @@
+use alloc::string::String;
+use core::fmt;
+
+/// How numbers are written: the digits used, how they are grouped and what separates the decimals.
+///
+/// By default, it uses ASCII digits without grouping, and `"."` as the decimal separator.
+///
+/// ```
+/// # use stringify_interval::{DisplayConfigConstant, NumberFormat, Text};
+/// let text = Text {
+///     number_format: NumberFormat {
+///         grouping_separator: Some(",".into()),
+///         ..Default::default()
+///     },
+///     ..Default::default()
+/// };
+/// let output = stringify_interval::without_date(
+///     chrono::Duration::days(1_234_567),
+///     &DisplayConfigConstant::none().with_days(),
+///     &text,
+/// );
+/// assert_eq!(output, Ok(String::from("1,234,567 days")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct NumberFormat {
+	/// In `"1[,]234[,]567"`, the bracketed parts. If none, digits are not grouped. The zeroes a unit is padded with are not grouped.
+	pub grouping_separator: Option<String>,
+	/// The number of digits in each group, counted from the right. A size of 0 disables grouping.
+	pub grouping_size: u8,
+	/// In `"3[.]5 hours"`, the bracketed part. Only used when a unit has decimals enabled.
+	pub decimal_separator: String,
+	/// The digits 0 through 9, in order.
+	pub digits: [char; 10],
+}
+
+impl NumberFormat {
+	pub const ASCII_DIGITS: [char; 10] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+	pub const ARABIC_INDIC_DIGITS: [char; 10] = ['٠', '١', '٢', '٣', '٤', '٥', '٦', '٧', '٨', '٩'];
+	pub const EXTENDED_ARABIC_INDIC_DIGITS: [char; 10] =
+		['۰', '۱', '۲', '۳', '۴', '۵', '۶', '۷', '۸', '۹'];
+	pub const DEVANAGARI_DIGITS: [char; 10] = ['०', '१', '२', '३', '४', '५', '६', '७', '८', '९'];
+	pub const BENGALI_DIGITS: [char; 10] = ['০', '১', '২', '৩', '৪', '৫', '৬', '৭', '৮', '৯'];
+
+	/// Writes an integer padded with zeroes to at least `pad` digits, grouping the digits if enabled. Only the digits of the number itself are grouped, so the padding goes in front of the groups, like `"001,234"`.
+	pub(crate) fn write_integer<W: fmt::Write + ?Sized>(
+		&self,
+		output: &mut W,
+		number: u64,
+		pad: u8,
+	) -> fmt::Result {
+		let (buffer, start) = decimal_digits(number, pad);
+		let digits = &buffer[start..];
+		let padding = digits.len() - digit_count(number);
+		let group_size = self.group_size();
+		for (index, &digit) in digits.iter().enumerate() {
+			if let (Some(separator), Some(group_size)) = (&self.grouping_separator, group_size) {
+				let remaining = digits.len() - index;
+				if index > padding && remaining % group_size == 0 {
+					output.write_str(separator)?;
+				}
+			}
+			output.write_char(self.digits[digit as usize])?;
+		}
+		Ok(())
+	}
+	/// Writes the decimal separator and exactly `decimals` digits of `digits`, padded with zeroes.
+	pub(crate) fn write_fraction<W: fmt::Write + ?Sized>(
+		&self,
+		output: &mut W,
+		digits: u64,
+		decimals: u8,
+	) -> fmt::Result {
+		output.write_str(&self.decimal_separator)?;
+		let (buffer, start) = decimal_digits(digits, decimals);
+		for &digit in &buffer[start..] {
+			output.write_char(self.digits[digit as usize])?;
+		}
+		Ok(())
+	}
+	/// The length in bytes that `write_integer` will produce.
+	pub(crate) fn integer_len(&self, number: u64, pad: u8) -> usize {
+		let mut length = self.digits_len(number, pad);
+		if let (Some(separator), Some(group_size)) = (&self.grouping_separator, self.group_size()) {
+			length += (digit_count(number) - 1) / group_size * separator.len();
+		}
+		length
+	}
+	/// The length in bytes that `write_fraction` will produce.
+	pub(crate) fn fraction_len(&self, digits: u64, decimals: u8) -> usize {
+		self.decimal_separator.len() + self.digits_len(digits, decimals)
+	}
+	/// The length in bytes of the digits of `number`, padded with zeroes to `pad` digits, since the digits need not all be as wide.
+	fn digits_len(&self, number: u64, pad: u8) -> usize {
+		let (buffer, start) = decimal_digits(number, pad);
+		buffer[start..]
+			.iter()
+			.map(|&digit| self.digits[digit as usize].len_utf8())
+			.sum()
+	}
+	fn group_size(&self) -> Option<usize> {
+		(self.grouping_size > 0).then_some(self.grouping_size as usize)
+	}
+}
+
+impl Default for NumberFormat {
+	fn default() -> Self {
+		Self {
+			grouping_separator: None,
+			grouping_size: 3,
+			decimal_separator: ".".into(),
+			digits: Self::ASCII_DIGITS,
+		}
+	}
+}
+
+/// The decimal digits of `number` as values from 0 through 9, padded with zeroes to at least `pad` digits. The digits are in the returned buffer from the returned index onward.
+fn decimal_digits(mut number: u64, pad: u8) -> ([u8; 256], usize) {
+	let mut buffer = [0; 256];
+	let mut start = buffer.len();
+	loop {
+		start -= 1;
+		buffer[start] = (number % 10) as u8;
+		number /= 10;
+		if number == 0 {
+			break;
+		}
+	}
+	let start = start.min(buffer.len() - pad as usize);
+	(buffer, start)
+}
+
+fn digit_count(number: u64) -> usize {
+	if number == 0 {
+		1
+	} else {
+		number.ilog10() as usize + 1
+	}
+}
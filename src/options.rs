@@ -1,15 +1,32 @@
-use std::ops::{Range, RangeFrom};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ops::{
+	Bound, Range, RangeBounds, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+};
 
+use chrono::{Datelike, Duration, Months, NaiveDateTime};
+
+use crate::countdown::Horizon;
+use crate::errors::StringifyError;
+use crate::number_format::NumberFormat;
+use crate::stringify::SECONDS_PER;
 use crate::threshold_map::ThresholdMap;
+use crate::util::Unit;
+use crate::work_calendar::BusinessLengths;
 
 /// All the strings that may be used to compose the final output. This owns the `String`s, so if it's performance-sensitive, you will want to reuse an instance of `Text`.
 /// 
 /// Each of the units uses a `ThresholdMap<String>`, which allows you to have different text for different counts, like for implementing plurals.
 /// 
-/// By default, `joiner`, `final_joiner` and `spacer` are `", "`, `Some(" and ")` and `" "`, respectively (except `String`s).
+/// The text for a unit is a template, where `{n}` is replaced by the count, like `"{n} days"`, `"{n}h"` or `"{n}. päivä"`. Text without `{n}`, like `"days"` from before templates, has the count and a space in front of it. Text with `{n}` more than once is rejected with `RepeatedCount`.
+/// 
+/// By default, `joiner` and `final_joiner` are `", "` and `Some(" and ")`, respectively (except `String`s), and numbers use the default `NumberFormat`. The phrases for `future`, `past` and `duration` are `"in {interval}"`, `"{interval} ago"` and `"for {interval}"`, without any different unit forms.
 #[derive(Debug, Clone)]
 pub struct Text {
+	pub centuries: ThresholdMap<String>,
+	pub decades: ThresholdMap<String>,
 	pub years: ThresholdMap<String>,
+	pub quarters: ThresholdMap<String>,
 	pub months: ThresholdMap<String>,
 	pub weeks: ThresholdMap<String>,
 	pub days: ThresholdMap<String>,
@@ -20,8 +37,169 @@ pub struct Text {
 	pub joiner: String,
 	/// In `"14 days, 6 hours[ and ]56 minutes"`, the bracketed part. If none, it will use the regular joiner.
 	pub final_joiner: Option<String>,
-	/// In `"14[ ]days, 6[ ]hours and 56[ ]minutes"`, the bracketed parts.
-	pub spacer: String,
+	/// How the counts are written, including the digits and decimal separator.
+	pub number_format: NumberFormat,
+	/// The phrase and unit forms for an interval in the future, like `"in 3 days"`. Used with `Phrasing::Relative`.
+	pub future: ContextText,
+	/// The phrase and unit forms for an interval in the past, like `"3 days ago"`. Used with `Phrasing::Relative`.
+	pub past: ContextText,
+	/// The phrase and unit forms for how long something lasts, like `"for 3 days"`. Used with `Phrasing::Duration`.
+	pub duration: ContextText,
+}
+
+/// The text for one grammatical context. In many languages, the form of a unit depends on the phrase around it, like the German `"3 Tage"`, but `"vor 3 Tagen"`.
+///
+/// Each unit can override the standalone text in `Text`. When a unit is `None`, the standalone text is used.
+#[derive(Debug, Clone)]
+pub struct ContextText {
+	/// The phrase around the whole output, where `{interval}` is replaced by the list of units, like `"in {interval}"` or `"{interval} ago"`.
+	pub phrase: String,
+	pub centuries: Option<ThresholdMap<String>>,
+	pub decades: Option<ThresholdMap<String>>,
+	pub years: Option<ThresholdMap<String>>,
+	pub quarters: Option<ThresholdMap<String>>,
+	pub months: Option<ThresholdMap<String>>,
+	pub weeks: Option<ThresholdMap<String>>,
+	pub days: Option<ThresholdMap<String>>,
+	pub hours: Option<ThresholdMap<String>>,
+	pub minutes: Option<ThresholdMap<String>>,
+	pub seconds: Option<ThresholdMap<String>>,
+}
+
+impl ContextText {
+	/// Uses the given phrase, and the standalone text for every unit.
+	pub fn with_phrase(phrase: impl Into<String>) -> Self {
+		Self {
+			phrase: phrase.into(),
+			centuries: None,
+			decades: None,
+			years: None,
+			quarters: None,
+			months: None,
+			weeks: None,
+			days: None,
+			hours: None,
+			minutes: None,
+			seconds: None,
+		}
+	}
+	/// Splits the phrase into the parts before and after the interval.
+	pub(crate) fn split_phrase(&self) -> (&str, &str) {
+		self.phrase
+			.split_once("{interval}")
+			.unwrap_or((&self.phrase, ""))
+	}
+	/// The text of each unit in this context, if it has its own.
+	fn units(&self) -> [Option<&ThresholdMap<String>>; 10] {
+		[
+			self.centuries.as_ref(),
+			self.decades.as_ref(),
+			self.years.as_ref(),
+			self.quarters.as_ref(),
+			self.months.as_ref(),
+			self.weeks.as_ref(),
+			self.days.as_ref(),
+			self.hours.as_ref(),
+			self.minutes.as_ref(),
+			self.seconds.as_ref(),
+		]
+	}
+}
+
+/// The grammatical context an interval is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GrammaticalContext {
+	Standalone,
+	Future,
+	Past,
+	Duration,
+}
+
+/// How the interval should be phrased.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Phrasing {
+	/// Just the units, like `"3 days"`.
+	#[default]
+	Standalone,
+	/// Relative to now, like `"in 3 days"` or `"3 days ago"`, depending on whether the interval is negative. Uses `Text::future` or `Text::past`.
+	Relative,
+	/// How long something lasts, like `"for 3 days"`. Uses `Text::duration`.
+	Duration,
+}
+
+impl Phrasing {
+	pub(crate) fn context(self, in_past: bool) -> GrammaticalContext {
+		match self {
+			Self::Standalone => GrammaticalContext::Standalone,
+			Self::Relative if in_past => GrammaticalContext::Past,
+			Self::Relative => GrammaticalContext::Future,
+			Self::Duration => GrammaticalContext::Duration,
+		}
+	}
+}
+
+/// How a date is moved by a number of months when its day doesn't exist in the month it lands in, like January 31st plus one month. This decides what counts as a whole month for the calendar units. With years, months and days displayed:
+///
+/// | From              | To                | `Clamp`            | `Overflow`            | `DayReached`          |
+/// |-------------------|-------------------|--------------------|-----------------------|-----------------------|
+/// | January 31, 2001  | February 28, 2001 | 1 month            | 28 days               | 28 days               |
+/// | January 31, 2001  | March 1, 2001     | 1 month and 1 day  | 29 days               | 1 month               |
+/// | January 31, 2001  | March 3, 2001     | 1 month and 3 days | 1 month               | 1 month and 2 days    |
+/// | January 31, 2001  | March 31, 2001    | 2 months           | 2 months              | 2 months              |
+/// | February 29, 2000 | February 28, 2001 | 1 year             | 11 months and 30 days | 11 months and 30 days |
+///
+/// Going back from a date works the same way, with the day adjusted in the month that is reached. Dates whose day exists in every month, like the 15th, are not affected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MonthArithmetic {
+	/// The date is moved to the last day of the month, so January 31st plus one month is February 28th.
+	#[default]
+	Clamp,
+	/// The days past the end of the month carry into the next one, so January 31st plus one month is March 3rd.
+	Overflow,
+	/// A month is only whole when its day is reached, which for a day that doesn't exist is at the start of the next month. So January 31st plus one month is March 1st.
+	DayReached,
+}
+
+impl MonthArithmetic {
+	/// The local time `months` months after `date`, or before it if `backwards`.
+	pub(crate) fn shift(
+		self,
+		date: NaiveDateTime,
+		months: u32,
+		backwards: bool,
+	) -> Option<NaiveDateTime> {
+		let months = Months::new(months);
+		let clamped = if backwards {
+			date.checked_sub_months(months)?
+		} else {
+			date.checked_add_months(months)?
+		};
+		if clamped.day() == date.day() {
+			return Some(clamped);
+		}
+		// The day doesn't exist, so `clamped` is the last day of the month, at the same time.
+		match self {
+			Self::Clamp => Some(clamped),
+			Self::Overflow => {
+				clamped.checked_add_signed(Duration::days(i64::from(date.day() - clamped.day())))
+			}
+			Self::DayReached => clamped.checked_add_signed(Duration::days(1)),
+		}
+	}
+}
+
+/// Which end of the interval the calendar units are counted from. Around the ends of months, the same span can have a different number of months and days depending on where they are counted from.
+///
+/// From January 31st to March 30th 2001, counting from the start is "1 month and 30 days", but counting back from the end is "1 month and 28 days".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Anchor {
+	/// From the reference date, forwards for a positive interval and backwards for a negative one.
+	#[default]
+	ReferenceDate,
+	/// Forwards from the earlier end of the interval, like for the time until a renewal.
+	Start,
+	/// Backwards from the later end of the interval, like for the age of an account.
+	End,
 }
 
 /// The strings that are used in building the output. These can be adjusted to change the format or do some degree of localisation.
@@ -33,9 +211,84 @@ impl Text {
 			_ => &self.joiner,
 		}
 	}
-	pub(crate) fn iter_units(&self) -> impl Iterator<Item = &ThresholdMap<String>> {
+	/// Checks that the text of every unit that may be displayed has a `{n}` for the count. To show only the number, use `"{n}"`.
+	pub(crate) fn validate(
+		&self,
+		context: GrammaticalContext,
+		config: DisplayConfigRef,
+	) -> Result<(), StringifyError> {
+		let listed = config
+			.constant_units
+			.into_iter()
+			.flatten()
+			.map(|unit| ((unit.labels.get(context), &unit.settings), unit.unit()));
+		for ((labels, settings), unit) in self
+			.iter_units(context)
+			.zip(config.iter())
+			.zip(Unit::ALL)
+			.take(config.built_in_count())
+			.chain(listed)
+		{
+			if settings.is_none() {
+				continue;
+			}
+			if labels.values().any(String::is_empty) {
+				return Err(StringifyError::EmptyLabel { unit });
+			}
+			if labels.values().any(|label| label.matches("{n}").nth(1).is_some()) {
+				return Err(StringifyError::RepeatedCount { unit });
+			}
+		}
+		Ok(())
+	}
+	/// Splits a unit template into the parts before the count, and the spacer and the part after it. Text without `{n}` comes after the count and a space.
+	pub(crate) fn split_template(template: &str) -> (&str, &str, &str) {
+		match template.split_once("{n}") {
+			Some((before, after)) => (before, "", after),
+			None => ("", " ", template),
+		}
+	}
+	pub(crate) fn context(&self, context: GrammaticalContext) -> Option<&ContextText> {
+		match context {
+			GrammaticalContext::Standalone => None,
+			GrammaticalContext::Future => Some(&self.future),
+			GrammaticalContext::Past => Some(&self.past),
+			GrammaticalContext::Duration => Some(&self.duration),
+		}
+	}
+	/// The text for each unit in the given context, falling back to the standalone text.
+	pub(crate) fn iter_units(
+		&self,
+		context: GrammaticalContext,
+	) -> impl Iterator<Item = &ThresholdMap<String>> {
+		let overrides = self
+			.context(context)
+			.map_or([None; 10], |context| context.units());
+		self.standalone_units()
+			.into_iter()
+			.zip(overrides)
+			.map(|(standalone, context)| context.unwrap_or(standalone))
+	}
+	/// The text for each unit in every context.
+	pub(crate) fn unit_labels(&self) -> impl Iterator<Item = UnitLabels> + '_ {
+		self.standalone_units()
+			.into_iter()
+			.zip(self.future.units())
+			.zip(self.past.units())
+			.zip(self.duration.units())
+			.map(|(((standalone, future), past), duration)| UnitLabels {
+				standalone: standalone.clone(),
+				future: future.cloned(),
+				past: past.cloned(),
+				duration: duration.cloned(),
+			})
+	}
+	fn standalone_units(&self) -> [&ThresholdMap<String>; 10] {
 		[
+			&self.centuries,
+			&self.decades,
 			&self.years,
+			&self.quarters,
 			&self.months,
 			&self.weeks,
 			&self.days,
@@ -43,46 +296,226 @@ impl Text {
 			&self.minutes,
 			&self.seconds,
 		]
-		.into_iter()
 	}
 }
 
 impl Default for Text {
 	fn default() -> Self {
 		Self {
-			years: ThresholdMap::from_iter("years", [(1, "year"), (2, "years")]).unwrap(),
-			months: ThresholdMap::from_iter("months", [(1, "month"), (2, "months")]).unwrap(),
-			weeks: ThresholdMap::from_iter("weeks", [(1, "week"), (2, "weeks")]).unwrap(),
-			days: ThresholdMap::from_iter("days", [(1, "day"), (2, "days")]).unwrap(),
-			hours: ThresholdMap::from_iter("hours", [(1, "hour"), (2, "hours")]).unwrap(),
-			minutes: ThresholdMap::from_iter("minutes", [(1, "minute"), (2, "minutes")]).unwrap(),
-			seconds: ThresholdMap::from_iter("seconds", [(1, "second"), (2, "seconds")]).unwrap(),
+			centuries: ThresholdMap::from_iter(
+				"{n} centuries",
+				[(1, "{n} century"), (2, "{n} centuries")],
+			)
+			.unwrap()
+			.with_fractional_value("{n} centuries"),
+			decades: ThresholdMap::from_iter(
+				"{n} decades",
+				[(1, "{n} decade"), (2, "{n} decades")],
+			)
+			.unwrap()
+			.with_fractional_value("{n} decades"),
+			years: ThresholdMap::from_iter("{n} years", [(1, "{n} year"), (2, "{n} years")])
+				.unwrap()
+				.with_fractional_value("{n} years"),
+			quarters: ThresholdMap::from_iter(
+				"{n} quarters",
+				[(1, "{n} quarter"), (2, "{n} quarters")],
+			)
+			.unwrap()
+			.with_fractional_value("{n} quarters"),
+			months: ThresholdMap::from_iter("{n} months", [(1, "{n} month"), (2, "{n} months")])
+				.unwrap()
+				.with_fractional_value("{n} months"),
+			weeks: ThresholdMap::from_iter("{n} weeks", [(1, "{n} week"), (2, "{n} weeks")])
+				.unwrap()
+				.with_fractional_value("{n} weeks"),
+			days: ThresholdMap::from_iter("{n} days", [(1, "{n} day"), (2, "{n} days")])
+				.unwrap()
+				.with_fractional_value("{n} days"),
+			hours: ThresholdMap::from_iter("{n} hours", [(1, "{n} hour"), (2, "{n} hours")])
+				.unwrap()
+				.with_fractional_value("{n} hours"),
+			minutes: ThresholdMap::from_iter(
+				"{n} minutes",
+				[(1, "{n} minute"), (2, "{n} minutes")],
+			)
+			.unwrap()
+			.with_fractional_value("{n} minutes"),
+			seconds: ThresholdMap::from_iter(
+				"{n} seconds",
+				[(1, "{n} second"), (2, "{n} seconds")],
+			)
+			.unwrap()
+			.with_fractional_value("{n} seconds"),
 			joiner: ", ".into(),
 			final_joiner: Some(" and ".into()),
-			spacer: " ".into(),
+			number_format: NumberFormat::default(),
+			future: ContextText::with_phrase("in {interval}"),
+			past: ContextText::with_phrase("{interval} ago"),
+			duration: ContextText::with_phrase("for {interval}"),
 		}
 	}
 }
 
-/// The range of values a unit should be displayed at, expressed in that unit. It implements `From<Range<u64>>` and `From<RangeFrom<u64>>`.
+impl Text {
+	/// Short labels with no spaces, like `"1d 10h 17m"`. The phrases are the same as by default.
+	pub fn compact() -> Self {
+		Self {
+			centuries: ThresholdMap::single_value("{n}c"),
+			decades: ThresholdMap::single_value("{n}dec"),
+			years: ThresholdMap::single_value("{n}y"),
+			quarters: ThresholdMap::single_value("{n}q"),
+			months: ThresholdMap::single_value("{n}mo"),
+			weeks: ThresholdMap::single_value("{n}w"),
+			days: ThresholdMap::single_value("{n}d"),
+			hours: ThresholdMap::single_value("{n}h"),
+			minutes: ThresholdMap::single_value("{n}m"),
+			seconds: ThresholdMap::single_value("{n}s"),
+			joiner: " ".into(),
+			final_joiner: None,
+			..Self::default()
+		}
+	}
+	/// German, like `"1 Tag, 10 Stunden und 17 Minuten"`, with the dative forms for `"in 3 Tagen"` and `"vor 3 Tagen"`, and a decimal comma.
+	pub fn german() -> Self {
+		let dative = ContextText {
+			centuries: Some(plural("{n} Jahrhundert", "{n} Jahrhunderten")),
+			decades: Some(plural("{n} Jahrzehnt", "{n} Jahrzehnten")),
+			years: Some(plural("{n} Jahr", "{n} Jahren")),
+			quarters: Some(plural("{n} Quartal", "{n} Quartalen")),
+			months: Some(plural("{n} Monat", "{n} Monaten")),
+			days: Some(plural("{n} Tag", "{n} Tagen")),
+			..ContextText::with_phrase("in {interval}")
+		};
+		Self {
+			centuries: plural("{n} Jahrhundert", "{n} Jahrhunderte"),
+			decades: plural("{n} Jahrzehnt", "{n} Jahrzehnte"),
+			years: plural("{n} Jahr", "{n} Jahre"),
+			quarters: plural("{n} Quartal", "{n} Quartale"),
+			months: plural("{n} Monat", "{n} Monate"),
+			weeks: plural("{n} Woche", "{n} Wochen"),
+			days: plural("{n} Tag", "{n} Tage"),
+			hours: plural("{n} Stunde", "{n} Stunden"),
+			minutes: plural("{n} Minute", "{n} Minuten"),
+			seconds: plural("{n} Sekunde", "{n} Sekunden"),
+			joiner: ", ".into(),
+			final_joiner: Some(" und ".into()),
+			number_format: NumberFormat {
+				decimal_separator: ",".into(),
+				..NumberFormat::default()
+			},
+			past: ContextText {
+				phrase: "vor {interval}".into(),
+				..dative.clone()
+			},
+			future: dative,
+			duration: ContextText::with_phrase("{interval} lang"),
+		}
+	}
+}
+
+/// The text for exactly one, and for any other count, including fractional ones.
+pub(crate) fn plural(one: &str, other: &str) -> ThresholdMap<String> {
+	ThresholdMap::from_iter(other, [(1, one), (2, other)])
+		.unwrap()
+		.with_fractional_value(other)
+}
+
+/// The range of values a unit should be displayed at, expressed in that unit. It converts from any of Rust's `u64` ranges, with the same meaning, so `0..10` does not include 10, but `0..=10` does.
 ///
 /// ```
 /// # use stringify_interval::DisplayRange;
 /// let my_range: DisplayRange = (0..10).into();
 /// assert!(!my_range.contains(15_u64));
+/// assert!(!my_range.contains(10_u64));
+/// let my_inclusive_range: DisplayRange = (..=10).into();
+/// assert!(my_inclusive_range.contains(10_u64));
 /// let my_half_open_range: DisplayRange = (3..).into();
 /// assert!(my_half_open_range.contains(15_u64));
 /// ```
-#[derive(Debug, Clone, Copy)]
+///
+/// It can instead be expressed as a window on the whole interval with `DisplayRange::interval`, like for showing seconds only when the interval is under 10 minutes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct DisplayRange {
 	lower: u64,
+	/// Exclusive. If none, there is no upper bound.
 	upper: Option<u64>,
+	measure: RangeMeasure,
+}
+
+/// What the bounds of a `DisplayRange` are compared to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RangeMeasure {
+	/// The count of the unit itself.
+	Count,
+	/// The whole interval, in seconds.
+	Interval,
 }
 
 impl DisplayRange {
+	/// A range on the whole interval rather than on the count of the unit. Negative bounds count as zero, and the interval is considered to the second.
+	///
+	/// ```
+	/// # use chrono::Duration;
+	/// # use stringify_interval::{DisplayConfigConstant, DisplayRange, DisplaySettings, Text};
+	/// let config = DisplayConfigConstant {
+	///     seconds: Some(DisplaySettings::new(
+	///         DisplayRange::interval(..Duration::minutes(10)),
+	///         0,
+	///         false,
+	///     )),
+	///     ..DisplayConfigConstant::default()
+	/// };
+	/// let text = |interval| stringify_interval::without_date(interval, &config, &Text::default());
+	/// assert_eq!(text(Duration::seconds(599)), Ok(String::from("9 minutes and 59 seconds")));
+	/// assert_eq!(text(Duration::seconds(601)), Ok(String::from("10 minutes")));
+	/// ```
+	pub fn interval(range: impl RangeBounds<Duration>) -> Self {
+		let seconds = |duration: &Duration| duration.num_seconds().max(0).unsigned_abs();
+		let lower = match range.start_bound() {
+			Bound::Included(start) => seconds(start),
+			Bound::Excluded(start) => seconds(start).saturating_add(1),
+			Bound::Unbounded => 0,
+		};
+		let upper = match range.end_bound() {
+			Bound::Included(end) => Self::exclusive(seconds(end)),
+			Bound::Excluded(end) => Some(seconds(end)),
+			Bound::Unbounded => None,
+		};
+		Self {
+			lower,
+			upper,
+			measure: RangeMeasure::Interval,
+		}
+	}
+	/// Whether the number is in the range, compared directly to the bounds, whether they are a count or a number of seconds.
 	pub fn contains(&self, number: impl Into<u64>) -> bool {
 		let number = number.into();
-		number >= self.lower && self.upper.map(|upper| number <= upper).unwrap_or(true)
+		number >= self.lower && self.upper.map(|upper| number < upper).unwrap_or(true)
+	}
+	/// Whether the range contains the count of its unit or the whole interval, depending on which it applies to.
+	pub(crate) fn contains_count_or_interval(&self, count: u64, interval_seconds: u64) -> bool {
+		match self.measure {
+			RangeMeasure::Count => self.contains(count),
+			RangeMeasure::Interval => self.contains(interval_seconds),
+		}
+	}
+	/// Limits the horizon to when the range starts or stops containing the count of a unit `unit_seconds` long, or the whole interval of `seconds`. The count of a calendar unit, which is 0 seconds long, changes with the calendar instead.
+	pub(crate) fn limit_horizon(&self, unit_seconds: u64, seconds: u64, horizon: &mut Horizon) {
+		let scale = match (self.measure, unit_seconds) {
+			(RangeMeasure::Interval, _) => 1,
+			(RangeMeasure::Count, 0) => return,
+			(RangeMeasure::Count, unit_seconds) => unit_seconds,
+		};
+		for bound in [Some(self.lower), self.upper].into_iter().flatten() {
+			if let Some(edge) = bound.checked_mul(scale) {
+				horizon.edge(seconds, edge);
+			}
+		}
+	}
+	/// The exclusive upper bound for an inclusive one, or none if that would be past `u64::MAX`.
+	fn exclusive(inclusive_upper: u64) -> Option<u64> {
+		inclusive_upper.checked_add(1)
 	}
 }
 
@@ -91,6 +524,17 @@ impl From<Range<u64>> for DisplayRange {
 		Self {
 			lower: value.start,
 			upper: Some(value.end),
+			measure: RangeMeasure::Count,
+		}
+	}
+}
+
+impl From<RangeInclusive<u64>> for DisplayRange {
+	fn from(value: RangeInclusive<u64>) -> Self {
+		Self {
+			lower: *value.start(),
+			upper: Self::exclusive(*value.end()),
+			measure: RangeMeasure::Count,
 		}
 	}
 }
@@ -100,16 +544,51 @@ impl From<RangeFrom<u64>> for DisplayRange {
 		Self {
 			lower: value.start,
 			upper: None,
+			measure: RangeMeasure::Count,
+		}
+	}
+}
+
+impl From<RangeTo<u64>> for DisplayRange {
+	fn from(value: RangeTo<u64>) -> Self {
+		Self {
+			lower: 0,
+			upper: Some(value.end),
+			measure: RangeMeasure::Count,
+		}
+	}
+}
+
+impl From<RangeToInclusive<u64>> for DisplayRange {
+	fn from(value: RangeToInclusive<u64>) -> Self {
+		Self {
+			lower: 0,
+			upper: Self::exclusive(value.end),
+			measure: RangeMeasure::Count,
+		}
+	}
+}
+
+impl From<RangeFull> for DisplayRange {
+	fn from(_: RangeFull) -> Self {
+		Self {
+			lower: 0,
+			upper: None,
+			measure: RangeMeasure::Count,
 		}
 	}
 }
 
-/// For an individual unit, the display range, the number of digits it should be padded to, and whether it should display even when it's zero.
+/// For an individual unit, the display range, the number of digits it should be padded to, whether it should display even when it's zero, and how many decimal places it may show when it's the smallest unit displayed.
 #[derive(Debug, Clone, Copy)]
 pub struct DisplaySettings {
 	pub range: DisplayRange,
 	pub pad: u8,
 	pub display_zero: bool,
+	/// The maximum number of decimal places to show, like `"3.5 hours"` instead of `"3 hours and 30 minutes"`. Trailing zeroes are left out.
+	///
+	/// This only applies to constant units, and only when the unit is the smallest one displayed. The interval is still only considered to the second.
+	pub decimals: u8,
 }
 
 impl DisplaySettings {
@@ -118,59 +597,214 @@ impl DisplaySettings {
 			range: range.into(),
 			pad,
 			display_zero,
+			decimals: 0,
+		}
+	}
+	pub fn with_decimals(mut self, decimals: u8) -> Self {
+		self.decimals = decimals;
+		self
+	}
+}
+
+/// One of the constant units an interval is split into, like shifts of 8 hours, sprints of 14 days or game ticks.
+///
+/// A list of them in `DisplayConfig::constant_units` replaces the built-in units from weeks to seconds. The units are split in the order of the list, each taking as many whole ones as fit in what the units before it left, so it should go from longest to shortest. `built_in_units` gives the built-in ones to start from.
+///
+/// ```
+/// # use chrono::Duration;
+/// # use stringify_interval::{ConstantUnit, DisplayConfigConstant, DisplaySettings, Text, ThresholdMap};
+/// let text = Text::default();
+/// let config = DisplayConfigConstant::none().with_hours();
+/// let mut units = config.built_in_units(&text);
+/// // After weeks and days.
+/// units.insert(2, ConstantUnit::new(
+///     "shifts",
+///     Duration::hours(8),
+///     DisplaySettings::new(.., 0, false),
+///     ThresholdMap::from_iter("{n} shifts", [(1, "{n} shift"), (2, "{n} shifts")]).unwrap(),
+/// ));
+/// let config = config.with_constant_units(units);
+/// let output = stringify_interval::without_date(Duration::hours(19), &config, &text);
+/// assert_eq!(output, Ok(String::from("2 shifts and 3 hours")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConstantUnit {
+	/// What the unit is called in errors, like `"shifts"`.
+	pub name: String,
+	/// The length of one of the unit. It is considered to the second, and must be at least one second.
+	pub length: Duration,
+	pub settings: Option<DisplaySettings>,
+	pub labels: UnitLabels,
+	/// The built-in unit this is, if any, so weeks and days are still counted in calendar or business days when those are asked for.
+	built_in: Option<Unit>,
+}
+
+impl ConstantUnit {
+	pub fn new(
+		name: impl Into<String>,
+		length: Duration,
+		settings: DisplaySettings,
+		labels: impl Into<UnitLabels>,
+	) -> Self {
+		Self {
+			name: name.into(),
+			length,
+			settings: Some(settings),
+			labels: labels.into(),
+			built_in: None,
+		}
+	}
+	/// The unit in errors.
+	pub(crate) fn unit(&self) -> Unit {
+		self.built_in
+			.clone()
+			.unwrap_or_else(|| Unit::Custom(self.name.clone()))
+	}
+}
+
+/// The text for a unit in each grammatical context, like the units in `Text` and `ContextText`. When a context is `None`, the standalone text is used.
+#[derive(Debug, Clone)]
+pub struct UnitLabels {
+	pub standalone: ThresholdMap<String>,
+	pub future: Option<ThresholdMap<String>>,
+	pub past: Option<ThresholdMap<String>>,
+	pub duration: Option<ThresholdMap<String>>,
+}
+
+impl UnitLabels {
+	pub(crate) fn get(&self, context: GrammaticalContext) -> &ThresholdMap<String> {
+		match context {
+			GrammaticalContext::Standalone => None,
+			GrammaticalContext::Future => self.future.as_ref(),
+			GrammaticalContext::Past => self.past.as_ref(),
+			GrammaticalContext::Duration => self.duration.as_ref(),
+		}
+		.unwrap_or(&self.standalone)
+	}
+}
+
+/// The same text in every context.
+impl From<ThresholdMap<String>> for UnitLabels {
+	fn from(standalone: ThresholdMap<String>) -> Self {
+		Self {
+			standalone,
+			future: None,
+			past: None,
+			duration: None,
 		}
 	}
 }
 
 pub(crate) trait OptionalDisplaySettings {
-	fn range_contains(&self, number: impl Into<u64>) -> bool;
+	/// Whether the unit is enabled and its range contains its count or the whole interval, depending on which the range applies to.
+	fn range_contains(&self, count: impl Into<u64>, interval_seconds: u64) -> bool;
+	/// The settings of a unit that is enabled, and so should have them.
+	fn settings(&self, unit: &Unit) -> Result<DisplaySettings, StringifyError>;
+	/// Limits the horizon to the edges of the range, if the unit is enabled. See `DisplayRange::limit_horizon`.
+	fn limit_horizon(&self, unit_seconds: u64, seconds: u64, horizon: &mut Horizon);
 }
 
 impl OptionalDisplaySettings for Option<DisplaySettings> {
-	fn range_contains(&self, number: impl Into<u64>) -> bool {
-		self.is_some_and(|config| config.range.contains(number))
+	fn range_contains(&self, count: impl Into<u64>, interval_seconds: u64) -> bool {
+		self.is_some_and(|config| {
+			config
+				.range
+				.contains_count_or_interval(count.into(), interval_seconds)
+		})
+	}
+	fn settings(&self, unit: &Unit) -> Result<DisplaySettings, StringifyError> {
+		self.ok_or_else(|| StringifyError::InternalMissingSettings { unit: unit.clone() })
+	}
+	fn limit_horizon(&self, unit_seconds: u64, seconds: u64, horizon: &mut Horizon) {
+		if let Some(settings) = self {
+			settings
+				.range
+				.limit_horizon(unit_seconds, seconds, horizon);
+		}
 	}
 }
 
-/// The display settings for each unit, including years and months.
+/// The display settings for each unit, including the calendar units from centuries to months, and how the interval should be phrased.
 #[derive(Debug, Clone)]
 pub struct DisplayConfig {
+	pub centuries: Option<DisplaySettings>,
+	pub decades: Option<DisplaySettings>,
 	pub years: Option<DisplaySettings>,
+	pub quarters: Option<DisplaySettings>,
 	pub months: Option<DisplaySettings>,
 	pub weeks: Option<DisplaySettings>,
 	pub days: Option<DisplaySettings>,
 	pub hours: Option<DisplaySettings>,
 	pub minutes: Option<DisplaySettings>,
 	pub seconds: Option<DisplaySettings>,
+	/// The constant units in the order they are split into, instead of the built-in ones from weeks to seconds, their settings above and their text in `Text`. See `ConstantUnit`.
+	///
+	/// The settings of weeks to seconds must then be `None`, or the interval is rejected with `ReplacedUnit`. `with_constant_units` sets them to `None`. Their text in `Text` is left unused.
+	pub constant_units: Option<Vec<ConstantUnit>>,
+	pub phrasing: Phrasing,
+	/// What counts as a whole month when the reference date's day doesn't exist in every month.
+	pub month_arithmetic: MonthArithmetic,
+	/// Which end of the interval the calendar units are counted from.
+	pub anchor: Anchor,
+	/// Whether weeks and days are counted as calendar days in the reference date's time zone, like years and months, rather than as 7 × 24 and 24 hours. Across a daylight saving time change, a calendar day is 23 or 25 hours long.
+	pub calendar_days: bool,
 }
 
 impl DisplayConfig {
 	pub fn default_no_inconstant() -> Self {
 		Self {
+			centuries: None,
+			decades: None,
 			years: None,
+			quarters: None,
 			months: None,
 			weeks: None,
 			days: Some(DisplaySettings::new(0.., 0, false)),
 			hours: Some(DisplaySettings::new(0.., 0, false)),
 			minutes: Some(DisplaySettings::new(0.., 0, false)),
 			seconds: Some(DisplaySettings::new(0..600, 0, false)),
+			constant_units: None,
+			phrasing: Phrasing::Standalone,
+			month_arithmetic: MonthArithmetic::Clamp,
+			anchor: Anchor::ReferenceDate,
+			calendar_days: false,
 		}
 	}
 	pub fn none() -> Self {
 		Self {
+			centuries: None,
+			decades: None,
 			years: None,
+			quarters: None,
 			months: None,
 			weeks: None,
 			days: None,
 			hours: None,
 			minutes: None,
 			seconds: None,
+			constant_units: None,
+			phrasing: Phrasing::Standalone,
+			month_arithmetic: MonthArithmetic::Clamp,
+			anchor: Anchor::ReferenceDate,
+			calendar_days: false,
 		}
 	}
+	pub fn with_centuries(mut self) -> Self {
+		self.centuries = Some(DisplaySettings::new(0.., 0, false));
+		self
+	}
+	pub fn with_decades(mut self) -> Self {
+		self.decades = Some(DisplaySettings::new(0.., 0, false));
+		self
+	}
 	pub fn with_years(mut self) -> Self {
 		self.years = Some(DisplaySettings::new(0.., 0, false));
 		self
 	}
+	pub fn with_quarters(mut self) -> Self {
+		self.quarters = Some(DisplaySettings::new(0.., 0, false));
+		self
+	}
 	pub fn with_months(mut self) -> Self {
 		self.months = Some(DisplaySettings::new(0.., 0, false));
 		self
@@ -195,23 +829,61 @@ impl DisplayConfig {
 		self.seconds = Some(DisplaySettings::new(0.., 0, false));
 		self
 	}
+	/// Uses the list of constant units instead of the built-in ones from weeks to seconds, and sets their settings to `None`.
+	pub fn with_constant_units(mut self, units: Vec<ConstantUnit>) -> Self {
+		self.weeks = None;
+		self.days = None;
+		self.hours = None;
+		self.minutes = None;
+		self.seconds = None;
+		self.constant_units = Some(units);
+		self
+	}
+	/// The built-in constant units from weeks to seconds, with their settings here and their text in `text`, to start a list for `constant_units` from.
+	pub fn built_in_units(&self, text: &Text) -> Vec<ConstantUnit> {
+		DisplayConfigRef::from(self).built_in_units(text)
+	}
+	pub fn with_phrasing(mut self, phrasing: Phrasing) -> Self {
+		self.phrasing = phrasing;
+		self
+	}
+	pub fn with_month_arithmetic(mut self, month_arithmetic: MonthArithmetic) -> Self {
+		self.month_arithmetic = month_arithmetic;
+		self
+	}
+	pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+		self.anchor = anchor;
+		self
+	}
+	pub fn with_calendar_days(mut self) -> Self {
+		self.calendar_days = true;
+		self
+	}
 }
 
 impl Default for DisplayConfig {
 	fn default() -> Self {
 		Self {
+			centuries: None,
+			decades: None,
 			years: Some(DisplaySettings::new(0.., 0, false)),
+			quarters: None,
 			months: Some(DisplaySettings::new(0.., 0, false)),
 			weeks: None,
 			days: Some(DisplaySettings::new(0.., 0, false)),
 			hours: Some(DisplaySettings::new(0.., 0, false)),
 			minutes: Some(DisplaySettings::new(0.., 0, false)),
 			seconds: Some(DisplaySettings::new(0..600, 0, false)),
+			constant_units: None,
+			phrasing: Phrasing::Standalone,
+			month_arithmetic: MonthArithmetic::Clamp,
+			anchor: Anchor::ReferenceDate,
+			calendar_days: false,
 		}
 	}
 }
 
-/// The display settings for each constant unit (so no years or months).
+/// The display settings for each constant unit (so no calendar units like years or months), and how the interval should be phrased.
 #[derive(Debug, Clone)]
 pub struct DisplayConfigConstant {
 	pub weeks: Option<DisplaySettings>,
@@ -219,6 +891,11 @@ pub struct DisplayConfigConstant {
 	pub hours: Option<DisplaySettings>,
 	pub minutes: Option<DisplaySettings>,
 	pub seconds: Option<DisplaySettings>,
+	/// The constant units in the order they are split into, instead of the built-in ones from weeks to seconds, their settings above and their text in `Text`. See `ConstantUnit`.
+	///
+	/// The settings of weeks to seconds must then be `None`, or the interval is rejected with `ReplacedUnit`. `with_constant_units` sets them to `None`. Their text in `Text` is left unused.
+	pub constant_units: Option<Vec<ConstantUnit>>,
+	pub phrasing: Phrasing,
 }
 
 impl DisplayConfigConstant {
@@ -229,6 +906,8 @@ impl DisplayConfigConstant {
 			hours: None,
 			minutes: None,
 			seconds: None,
+			constant_units: None,
+			phrasing: Phrasing::Standalone,
 		}
 	}
 	pub fn with_weeks(mut self) -> Self {
@@ -251,6 +930,24 @@ impl DisplayConfigConstant {
 		self.seconds = Some(DisplaySettings::new(0.., 0, false));
 		self
 	}
+	/// Uses the list of constant units instead of the built-in ones from weeks to seconds, and sets their settings to `None`.
+	pub fn with_constant_units(mut self, units: Vec<ConstantUnit>) -> Self {
+		self.weeks = None;
+		self.days = None;
+		self.hours = None;
+		self.minutes = None;
+		self.seconds = None;
+		self.constant_units = Some(units);
+		self
+	}
+	/// The built-in constant units from weeks to seconds, with their settings here and their text in `text`, to start a list for `constant_units` from.
+	pub fn built_in_units(&self, text: &Text) -> Vec<ConstantUnit> {
+		DisplayConfigRef::from(self).built_in_units(text)
+	}
+	pub fn with_phrasing(mut self, phrasing: Phrasing) -> Self {
+		self.phrasing = phrasing;
+		self
+	}
 }
 
 impl Default for DisplayConfigConstant {
@@ -261,6 +958,8 @@ impl Default for DisplayConfigConstant {
 			hours: Some(DisplaySettings::new(0.., 0, false)),
 			minutes: Some(DisplaySettings::new(0.., 0, false)),
 			seconds: Some(DisplaySettings::new(0..600, 0, false)),
+			constant_units: None,
+			phrasing: Phrasing::Standalone,
 		}
 	}
 }
@@ -268,35 +967,132 @@ impl Default for DisplayConfigConstant {
 impl From<DisplayConfigConstant> for DisplayConfig {
 	fn from(value: DisplayConfigConstant) -> Self {
 		Self {
+			centuries: None,
+			decades: None,
 			years: None,
+			quarters: None,
 			months: None,
 			weeks: value.weeks,
 			days: value.days,
 			hours: value.hours,
 			minutes: value.minutes,
 			seconds: value.seconds,
+			constant_units: value.constant_units,
+			phrasing: value.phrasing,
+			month_arithmetic: MonthArithmetic::Clamp,
+			anchor: Anchor::ReferenceDate,
+			calendar_days: false,
 		}
 	}
 }
 
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct DisplayConfigRef<'l> {
+	pub(crate) centuries: &'l Option<DisplaySettings>,
+	pub(crate) decades: &'l Option<DisplaySettings>,
 	pub(crate) years: &'l Option<DisplaySettings>,
+	pub(crate) quarters: &'l Option<DisplaySettings>,
 	pub(crate) months: &'l Option<DisplaySettings>,
 	pub(crate) weeks: &'l Option<DisplaySettings>,
 	pub(crate) days: &'l Option<DisplaySettings>,
 	pub(crate) hours: &'l Option<DisplaySettings>,
 	pub(crate) minutes: &'l Option<DisplaySettings>,
 	pub(crate) seconds: &'l Option<DisplaySettings>,
+	pub(crate) constant_units: Option<&'l [ConstantUnit]>,
+	pub(crate) phrasing: Phrasing,
+	pub(crate) month_arithmetic: MonthArithmetic,
+	pub(crate) anchor: Anchor,
+	pub(crate) calendar_days: bool,
+	/// The lengths weeks and days have when counting business time.
+	pub(crate) business: Option<BusinessLengths>,
 }
 
 impl<'l> DisplayConfigRef<'l> {
 	pub(crate) fn has_inconstant_enabled(&self) -> bool {
-		self.years.is_some() || self.months.is_some()
+		self.iter()
+			.take(Unit::CALENDAR_COUNT)
+			.any(|settings| settings.is_some())
+			|| self.calendar_days
+				&& self.iter_settings().any(|(settings, unit)| {
+					settings.is_some() && matches!(unit, Unit::Weeks | Unit::Days)
+				})
+	}
+	/// Checks for display ranges that can never contain anything because they end before they start, for constant units shorter than a second, and for settings of built-in constant units that a list of constant units replaces.
+	pub(crate) fn validate(&self) -> Result<(), StringifyError> {
+		if self.constant_units.is_some() {
+			let mut replaced = self.iter().zip(Unit::ALL).skip(Unit::CALENDAR_COUNT);
+			if let Some((_, unit)) = replaced.find(|(settings, _)| settings.is_some()) {
+				return Err(StringifyError::ReplacedUnit { unit });
+			}
+		}
+		for constant_unit in self.constant_units.into_iter().flatten() {
+			if constant_unit.length.num_seconds() < 1 {
+				return Err(StringifyError::InvalidLength {
+					unit: constant_unit.unit(),
+					length: constant_unit.length,
+				});
+			}
+		}
+		for (settings, unit) in self.iter_settings() {
+			let Some(DisplaySettings { range, .. }) = settings else {
+				continue;
+			};
+			if let Some(upper) = range.upper {
+				if range.lower >= upper {
+					return Err(StringifyError::InvertedRange {
+						unit,
+						lower: range.lower,
+						upper,
+					});
+				}
+			}
+		}
+		Ok(())
+	}
+	/// How many of the built-in units are used: all of them, or only the calendar units when there is a list of constant units.
+	pub(crate) fn built_in_count(&self) -> usize {
+		match self.constant_units {
+			Some(_) => Unit::CALENDAR_COUNT,
+			None => Unit::ALL.len(),
+		}
+	}
+	/// The settings of each unit used, with the unit they are for. The calendar units come first, then the constant units in order.
+	pub(crate) fn iter_settings(
+		&self,
+	) -> impl Iterator<Item = (&'l Option<DisplaySettings>, Unit)> {
+		let listed = self
+			.constant_units
+			.into_iter()
+			.flatten()
+			.map(|unit| (&unit.settings, unit.unit()));
+		self.iter()
+			.zip(Unit::ALL)
+			.take(self.built_in_count())
+			.chain(listed)
+	}
+	fn built_in_units(&self, text: &Text) -> Vec<ConstantUnit> {
+		self.iter()
+			.zip(text.unit_labels())
+			.zip(SECONDS_PER.iter())
+			.zip(Unit::ALL)
+			.skip(Unit::CALENDAR_COUNT)
+			.map(|(((settings, labels), &seconds), unit)| ConstantUnit {
+				name: unit.to_string(),
+				length: Duration::seconds(seconds as i64),
+				settings: *settings,
+				labels,
+				built_in: Some(unit),
+			})
+			.collect()
 	}
-	pub(crate) fn iter(&self) -> impl Iterator<Item = &Option<DisplaySettings>> {
+	pub(crate) fn iter(
+		&self,
+	) -> impl ExactSizeIterator<Item = &'l Option<DisplaySettings>> + DoubleEndedIterator + 'l {
 		[
+			self.centuries,
+			self.decades,
 			self.years,
+			self.quarters,
 			self.months,
 			self.weeks,
 			self.days,
@@ -311,13 +1107,22 @@ impl<'l> DisplayConfigRef<'l> {
 impl<'l> From<&'l DisplayConfigConstant> for DisplayConfigRef<'l> {
 	fn from(value: &'l DisplayConfigConstant) -> Self {
 		Self {
+			centuries: &None,
+			decades: &None,
 			years: &None,
+			quarters: &None,
 			months: &None,
 			weeks: &value.weeks,
 			days: &value.days,
 			hours: &value.hours,
 			minutes: &value.minutes,
 			seconds: &value.seconds,
+			constant_units: value.constant_units.as_deref(),
+			phrasing: value.phrasing,
+			month_arithmetic: MonthArithmetic::Clamp,
+			anchor: Anchor::ReferenceDate,
+			calendar_days: false,
+			business: None,
 		}
 	}
 }
@@ -325,13 +1130,22 @@ impl<'l> From<&'l DisplayConfigConstant> for DisplayConfigRef<'l> {
 impl<'l> From<&'l DisplayConfig> for DisplayConfigRef<'l> {
 	fn from(value: &'l DisplayConfig) -> Self {
 		Self {
+			centuries: &value.centuries,
+			decades: &value.decades,
 			years: &value.years,
+			quarters: &value.quarters,
 			months: &value.months,
 			weeks: &value.weeks,
 			days: &value.days,
 			hours: &value.hours,
 			minutes: &value.minutes,
 			seconds: &value.seconds,
+			constant_units: value.constant_units.as_deref(),
+			phrasing: value.phrasing,
+			month_arithmetic: value.month_arithmetic,
+			anchor: value.anchor,
+			calendar_days: value.calendar_days,
+			business: None,
 		}
 	}
 }
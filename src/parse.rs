@@ -0,0 +1,278 @@
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use chrono::Duration;
+
+use crate::errors::ParseError;
+use crate::number_format::NumberFormat;
+use crate::options::{GrammaticalContext, Text};
+use crate::stringify::SECONDS_PER;
+use crate::util::Unit;
+
+const NANOSECONDS_PER_SECOND: i128 = 1_000_000_000;
+
+/// Parses an interval written like the output of `without_date`, such as `"1 day, 10 hours and 17 minutes"`, or `"2h 5m"` with `Text::compact`, back into a `Duration`.
+///
+/// The constant units, weeks to seconds, are recognised by any of their labels in `text`, in any grammatical context. They can come in any order, and whitespace, commas and the joiners between them are skipped. Counts can have decimals, with the `NumberFormat`'s decimal separator or `"."`. An interval in the past phrase, like `"3 days ago"`, is negative.
+///
+/// ```
+/// # use stringify_interval::Text;
+/// let interval = stringify_interval::parse("1 day, 10 hours and 17 minutes", &Text::default());
+/// assert_eq!(interval, Ok(chrono::Duration::minutes(2057)));
+/// let interval = stringify_interval::parse("2h5m", &Text::compact());
+/// assert_eq!(interval, Ok(chrono::Duration::minutes(125)));
+/// ```
+pub fn parse(input: &str, text: &Text) -> Result<Duration, ParseError> {
+	let labels = Label::list(text);
+	let (mut rest, negative) = strip_phrase(input.trim(), text);
+	let separators = [
+		text.joiner.trim(),
+		text.final_joiner.as_deref().unwrap_or("").trim(),
+		",",
+	];
+
+	let mut nanoseconds: i128 = 0;
+	let mut any = false;
+	loop {
+		rest = skip_separators(rest, &separators);
+		if rest.is_empty() {
+			break;
+		}
+		// The longest match wins, so "days" isn't read as "day" followed by "s".
+		let Some((label, number, after_label)) = labels
+			.iter()
+			.filter_map(|label| {
+				let (number, rest) = label.parse(rest, &text.number_format)?;
+				Some((label, number, rest))
+			})
+			.min_by_key(|(_, _, rest)| rest.len())
+		else {
+			return Err(match parse_number(rest, &text.number_format) {
+				Some((_, after_number)) => match first_word(after_number.trim_start()) {
+					"" => ParseError::MissingUnit {
+						number: rest[..rest.len() - after_number.len()].into(),
+					},
+					found => ParseError::UnknownUnit {
+						found: found.into(),
+						units: Label::names(&labels),
+					},
+				},
+				None => ParseError::ExpectedNumber {
+					found: first_word(rest).into(),
+				},
+			});
+		};
+		nanoseconds = number
+			.nanoseconds(i128::from(label.seconds) * NANOSECONDS_PER_SECOND)
+			.and_then(|element| nanoseconds.checked_add(element))
+			.ok_or(ParseError::OutOfRange)?;
+		any = true;
+		rest = after_label;
+	}
+	if !any {
+		return Err(ParseError::Empty);
+	}
+
+	let interval = to_duration(nanoseconds).ok_or(ParseError::OutOfRange)?;
+	Ok(if negative { -interval } else { interval })
+}
+
+fn to_duration(nanoseconds: i128) -> Option<Duration> {
+	let seconds = i64::try_from(nanoseconds / NANOSECONDS_PER_SECOND).ok()?;
+	// Less than a second, so it fits.
+	let rest = Duration::nanoseconds((nanoseconds % NANOSECONDS_PER_SECOND) as i64);
+	Duration::try_seconds(seconds)?.checked_add(&rest)
+}
+
+/// A unit's text around the count, from one of its templates.
+struct Label<'l> {
+	before: &'l str,
+	after: &'l str,
+	seconds: u64,
+}
+
+impl<'l> Label<'l> {
+	/// The labels of the constant units in all contexts, without repeats.
+	fn list(text: &'l Text) -> Vec<Self> {
+		let mut labels: Vec<Self> = Vec::new();
+		for context in [
+			GrammaticalContext::Standalone,
+			GrammaticalContext::Future,
+			GrammaticalContext::Past,
+			GrammaticalContext::Duration,
+		] {
+			for (map, &seconds) in text
+				.iter_units(context)
+				.zip(SECONDS_PER.iter())
+				.skip(Unit::CALENDAR_COUNT)
+			{
+				for template in map.values() {
+					let (before, _, after) = Text::split_template(template);
+					let label = Self {
+						before: before.trim(),
+						after: after.trim(),
+						seconds,
+					};
+					if !labels
+						.iter()
+						.any(|known| (known.before, known.after) == (label.before, label.after))
+					{
+						labels.push(label);
+					}
+				}
+			}
+		}
+		labels
+	}
+	/// The labels as they would be written, to suggest when a unit isn't known.
+	fn names(labels: &[Self]) -> Vec<String> {
+		labels
+			.iter()
+			.map(|label| match (label.before, label.after) {
+				("", after) => after.to_owned(),
+				(before, "") => before.to_owned(),
+				(before, after) => format!("{before} … {after}"),
+			})
+			.collect()
+	}
+	/// The count, and what is left after the label, if `input` starts with this label.
+	fn parse<'i>(&self, input: &'i str, format: &NumberFormat) -> Option<(Number, &'i str)> {
+		let input = match self.before {
+			"" => input,
+			before => input.strip_prefix(before)?.trim_start(),
+		};
+		let (number, rest) = parse_number(input, format)?;
+		if self.after.is_empty() {
+			return Some((number, rest));
+		}
+		let rest = rest.trim_start().strip_prefix(self.after)?;
+		// A label ending in a letter has to end the word, so "5min" isn't "5m" and "in".
+		let ends_word =
+			!self.after.ends_with(char::is_alphabetic) || !rest.starts_with(char::is_alphabetic);
+		ends_word.then_some((number, rest))
+	}
+}
+
+/// A count, with the digits after the decimal separator.
+#[derive(Debug, Clone, Copy)]
+struct Number {
+	whole: u64,
+	fraction: u64,
+	decimals: u32,
+}
+
+impl Number {
+	/// The value of this many of a unit `per_unit` nanoseconds long, rounded down to the nanosecond.
+	fn nanoseconds(self, per_unit: i128) -> Option<i128> {
+		let whole = i128::from(self.whole).checked_mul(per_unit)?;
+		let fraction = i128::from(self.fraction) * per_unit / 10_i128.pow(self.decimals);
+		whole.checked_add(fraction)
+	}
+}
+
+/// Parses a count at the start of `input`, in the format's digits or ASCII digits, and returns what is left.
+fn parse_number<'i>(input: &'i str, format: &NumberFormat) -> Option<(Number, &'i str)> {
+	let digit = |c: char| {
+		c.to_digit(10)
+			.or_else(|| format.digits.iter().position(|&d| d == c).map(|d| d as u32))
+	};
+	let starts_with_digit = |input: &str| input.chars().next().and_then(digit).is_some();
+	let grouping = format
+		.grouping_separator
+		.as_deref()
+		.filter(|s| !s.is_empty());
+
+	let mut rest = input;
+	let mut whole: u64 = 0;
+	let mut any = false;
+	while let Some(c) = rest.chars().next() {
+		if let Some(value) = digit(c) {
+			// Too large a count is out of range anyway, so it's kept as large as it gets.
+			whole = whole.saturating_mul(10).saturating_add(value.into());
+			any = true;
+			rest = &rest[c.len_utf8()..];
+		} else if let Some(after) = grouping
+			.and_then(|separator| rest.strip_prefix(separator))
+			.filter(|after| any && starts_with_digit(after))
+		{
+			rest = after;
+		} else {
+			break;
+		}
+	}
+	if !any {
+		return None;
+	}
+
+	let mut number = Number {
+		whole,
+		fraction: 0,
+		decimals: 0,
+	};
+	if let Some(after) = [format.decimal_separator.as_str(), "."]
+		.into_iter()
+		.filter(|separator| !separator.is_empty())
+		.find_map(|separator| rest.strip_prefix(separator))
+		.filter(|after| starts_with_digit(after))
+	{
+		rest = after;
+		while let Some(c) = rest.chars().next() {
+			let Some(value) = digit(c) else {
+				break;
+			};
+			// Digits past the 18th are too small to matter.
+			if number.decimals < 18 {
+				number.fraction = number.fraction * 10 + u64::from(value);
+				number.decimals += 1;
+			}
+			rest = &rest[c.len_utf8()..];
+		}
+	}
+	Some((number, rest))
+}
+
+/// Removes the phrase around the interval, if any, and returns whether it was the phrase for the past.
+fn strip_phrase<'i>(input: &'i str, text: &Text) -> (&'i str, bool) {
+	for (context, negative) in [
+		(&text.past, true),
+		(&text.future, false),
+		(&text.duration, false),
+	] {
+		let (before, after) = context.split_phrase();
+		let (before, after) = (before.trim_start(), after.trim_end());
+		if before.is_empty() && after.is_empty() {
+			continue;
+		}
+		if let Some(inner) = input
+			.strip_prefix(before)
+			.and_then(|inner| inner.strip_suffix(after))
+		{
+			return (inner.trim(), negative);
+		}
+	}
+	(input, false)
+}
+
+fn skip_separators<'i>(mut input: &'i str, separators: &[&str]) -> &'i str {
+	loop {
+		let trimmed = input.trim_start();
+		match separators
+			.iter()
+			.filter(|separator| !separator.is_empty())
+			.find_map(|separator| trimmed.strip_prefix(separator))
+		{
+			Some(rest) => input = rest,
+			None => return trimmed,
+		}
+	}
+}
+
+/// The start of `input`, up to whitespace or a digit, to show what couldn't be parsed.
+fn first_word(input: &str) -> &str {
+	let end = input
+		.find(|c: char| c.is_whitespace() || c.is_ascii_digit())
+		.unwrap_or(input.len());
+	&input[..end]
+}
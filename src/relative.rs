@@ -0,0 +1,92 @@
+use alloc::string::String;
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+
+/// The words for a date in the calendar day, week, month or year right before or after the reference date, like "yesterday" or "next month", for `relative_date`. A word that is `None` isn't used, so the next larger one may be instead.
+///
+/// By default, these are the English words, with weeks starting on Monday.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelativeText {
+	pub yesterday: Option<String>,
+	pub tomorrow: Option<String>,
+	pub last_week: Option<String>,
+	pub next_week: Option<String>,
+	pub last_month: Option<String>,
+	pub next_month: Option<String>,
+	pub last_year: Option<String>,
+	pub next_year: Option<String>,
+	/// The day calendar weeks start on.
+	pub first_day_of_week: Weekday,
+}
+
+impl RelativeText {
+	/// No words at all, so the interval is always stringified as usual.
+	pub fn none() -> Self {
+		Self {
+			yesterday: None,
+			tomorrow: None,
+			last_week: None,
+			next_week: None,
+			last_month: None,
+			next_month: None,
+			last_year: None,
+			next_year: None,
+			first_day_of_week: Weekday::Mon,
+		}
+	}
+	/// The word for the target date relative to the reference date, trying days, weeks, months and years in that order.
+	pub(crate) fn word(&self, reference: NaiveDate, target: NaiveDate) -> Option<&str> {
+		let week_start = |date: NaiveDate| {
+			let days_since = (date.weekday().num_days_from_monday() + 7
+				- self.first_day_of_week.num_days_from_monday())
+				% 7;
+			date.checked_sub_days(Days::new(days_since.into()))
+		};
+		let weeks = week_start(target)
+			.zip(week_start(reference))
+			.map_or(0, |(target, reference)| {
+				target.signed_duration_since(reference).num_weeks()
+			});
+		let months = |date: NaiveDate| i64::from(date.year()) * 12 + i64::from(date.month0());
+		[
+			(
+				target.signed_duration_since(reference).num_days(),
+				&self.yesterday,
+				&self.tomorrow,
+			),
+			(weeks, &self.last_week, &self.next_week),
+			(
+				months(target) - months(reference),
+				&self.last_month,
+				&self.next_month,
+			),
+			(
+				i64::from(target.year()) - i64::from(reference.year()),
+				&self.last_year,
+				&self.next_year,
+			),
+		]
+		.into_iter()
+		.find_map(|(difference, last, next)| match difference {
+			-1 => last.as_deref(),
+			1 => next.as_deref(),
+			_ => None,
+		})
+	}
+}
+
+impl Default for RelativeText {
+	fn default() -> Self {
+		Self {
+			yesterday: Some("yesterday".into()),
+			tomorrow: Some("tomorrow".into()),
+			last_week: Some("last week".into()),
+			next_week: Some("next week".into()),
+			last_month: Some("last month".into()),
+			next_month: Some("next month".into()),
+			last_year: Some("last year".into()),
+			next_year: Some("next year".into()),
+			first_day_of_week: Weekday::Mon,
+		}
+	}
+}
@@ -0,0 +1,239 @@
+//! Adapters to serialize and deserialize `chrono::Duration` fields as text like "2 hours and 5 minutes", for use with `#[serde(with = "...")]`.
+//!
+//! Durations are written with days, hours, minutes and seconds, all the way down to the second, so they read back the same, except for any fraction of a second, which is dropped. Negative durations are written in the past phrase, like "5 minutes ago". They are read with `parse`, with the preset's text or else `Text::compact`, so anything it accepts is fine, like "2h5m" or "1.5 days".
+//!
+//! ```
+//! # use chrono::Duration;
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Config {
+//!     #[serde(with = "stringify_interval::serde::human")]
+//!     timeout: Duration,
+//!     #[serde(with = "stringify_interval::serde::compact::option", default)]
+//!     retry: Option<Duration>,
+//! }
+//!
+//! let config: Config = serde_json::from_str(r#"{ "timeout": "2 hours and 5 minutes", "retry": "1m30s" }"#).unwrap();
+//! assert_eq!(config.timeout, Duration::minutes(125));
+//! assert_eq!(config.retry, Some(Duration::seconds(90)));
+//! assert_eq!(
+//!     serde_json::to_string(&config).unwrap(),
+//!     r#"{"timeout":"2 hours and 5 minutes","retry":"1m 30s"}"#,
+//! );
+//! ```
+//!
+//! For another `Text`, implement `TextPreset` and use the generic functions:
+//!
+//! ```
+//! # use chrono::Duration;
+//! # use stringify_interval::{serde::TextPreset, Text};
+//! struct German;
+//!
+//! impl TextPreset for German {
+//!     fn text() -> Text {
+//!         Text::german()
+//!     }
+//! }
+//!
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Config {
+//!     #[serde(
+//!         serialize_with = "stringify_interval::serde::serialize::<German, _>",
+//!         deserialize_with = "stringify_interval::serde::deserialize::<German, _>"
+//!     )]
+//!     timeout: Duration,
+//! }
+//!
+//! let config: Config = serde_json::from_str(r#"{ "timeout": "1 Tag und 2 Stunden" }"#).unwrap();
+//! assert_eq!(config.timeout, Duration::hours(26));
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use ::serde::{de, Deserializer, Serializer};
+use chrono::Duration;
+
+use crate::options::{DisplayConfigConstant, Phrasing, Text};
+
+/// The `Text` durations are written and read with, by the generic functions in this module.
+pub trait TextPreset {
+	fn text() -> Text;
+}
+
+/// `Text::default`, for durations like "2 hours and 5 minutes".
+pub struct Human;
+
+impl TextPreset for Human {
+	fn text() -> Text {
+		Text::default()
+	}
+}
+
+/// `Text::compact`, for durations like "2h 5m".
+pub struct Compact;
+
+impl TextPreset for Compact {
+	fn text() -> Text {
+		Text::compact()
+	}
+}
+
+/// Serializes a duration as text in `P`'s `Text`.
+pub fn serialize<P: TextPreset, S: Serializer>(
+	interval: &Duration,
+	serializer: S,
+) -> Result<S::Ok, S::Error> {
+	let phrasing = if *interval < Duration::zero() {
+		Phrasing::Relative
+	} else {
+		Phrasing::Standalone
+	};
+	let config = DisplayConfigConstant::none()
+		.with_days()
+		.with_hours()
+		.with_minutes()
+		.with_seconds()
+		.with_phrasing(phrasing);
+	let text = crate::without_date(*interval, &config, &P::text())
+		.map_err(<S::Error as ::serde::ser::Error>::custom)?;
+	serializer.serialize_str(&text)
+}
+
+/// Deserializes a duration from text in `P`'s `Text`.
+pub fn deserialize<'de, P: TextPreset, D: Deserializer<'de>>(
+	deserializer: D,
+) -> Result<Duration, D::Error> {
+	deserializer.deserialize_str(DurationVisitor::<P>(PhantomData))
+}
+
+/// Serializes an optional duration as text in `P`'s `Text`, or as nothing.
+pub fn serialize_option<P: TextPreset, S: Serializer>(
+	interval: &Option<Duration>,
+	serializer: S,
+) -> Result<S::Ok, S::Error> {
+	match interval {
+		Some(interval) => serializer.serialize_some(&Serialized::<P>(*interval, PhantomData)),
+		None => serializer.serialize_none(),
+	}
+}
+
+/// Deserializes an optional duration from text in `P`'s `Text`, or from nothing.
+pub fn deserialize_option<'de, P: TextPreset, D: Deserializer<'de>>(
+	deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+	deserializer.deserialize_option(OptionVisitor::<P>(PhantomData))
+}
+
+/// For `#[serde(with = "stringify_interval::serde::human")]`, with `Text::default`.
+pub mod human {
+	use ::serde::{Deserializer, Serializer};
+	use chrono::Duration;
+
+	use super::Human;
+
+	pub fn serialize<S: Serializer>(interval: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+		super::serialize::<Human, S>(interval, serializer)
+	}
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+		super::deserialize::<Human, D>(deserializer)
+	}
+
+	/// For `Option<Duration>` fields, with `Text::default`.
+	pub mod option {
+		use ::serde::{Deserializer, Serializer};
+		use chrono::Duration;
+
+		use super::super::Human;
+
+		pub fn serialize<S: Serializer>(
+			interval: &Option<Duration>,
+			serializer: S,
+		) -> Result<S::Ok, S::Error> {
+			super::super::serialize_option::<Human, S>(interval, serializer)
+		}
+		pub fn deserialize<'de, D: Deserializer<'de>>(
+			deserializer: D,
+		) -> Result<Option<Duration>, D::Error> {
+			super::super::deserialize_option::<Human, D>(deserializer)
+		}
+	}
+}
+
+/// For `#[serde(with = "stringify_interval::serde::compact")]`, with `Text::compact`.
+pub mod compact {
+	use ::serde::{Deserializer, Serializer};
+	use chrono::Duration;
+
+	use super::Compact;
+
+	pub fn serialize<S: Serializer>(interval: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+		super::serialize::<Compact, S>(interval, serializer)
+	}
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+		super::deserialize::<Compact, D>(deserializer)
+	}
+
+	/// For `Option<Duration>` fields, with `Text::compact`.
+	pub mod option {
+		use ::serde::{Deserializer, Serializer};
+		use chrono::Duration;
+
+		use super::super::Compact;
+
+		pub fn serialize<S: Serializer>(
+			interval: &Option<Duration>,
+			serializer: S,
+		) -> Result<S::Ok, S::Error> {
+			super::super::serialize_option::<Compact, S>(interval, serializer)
+		}
+		pub fn deserialize<'de, D: Deserializer<'de>>(
+			deserializer: D,
+		) -> Result<Option<Duration>, D::Error> {
+			super::super::deserialize_option::<Compact, D>(deserializer)
+		}
+	}
+}
+
+/// A duration to serialize inside `Some`.
+struct Serialized<P>(Duration, PhantomData<P>);
+
+impl<P: TextPreset> ::serde::Serialize for Serialized<P> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serialize::<P, S>(&self.0, serializer)
+	}
+}
+
+struct DurationVisitor<P>(PhantomData<P>);
+
+impl<'de, P: TextPreset> de::Visitor<'de> for DurationVisitor<P> {
+	type Value = Duration;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("a duration like \"2 hours and 5 minutes\"")
+	}
+	fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+		// Short labels are read with any preset, like `clap::DurationParser::default` does.
+		crate::parse(value, &P::text())
+			.or_else(|error| crate::parse(value, &Text::compact()).map_err(|_| error))
+			.map_err(E::custom)
+	}
+}
+
+struct OptionVisitor<P>(PhantomData<P>);
+
+impl<'de, P: TextPreset> de::Visitor<'de> for OptionVisitor<P> {
+	type Value = Option<Duration>;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("a duration like \"2 hours and 5 minutes\", or nothing")
+	}
+	fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+		Ok(None)
+	}
+	fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+		Ok(None)
+	}
+	fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		deserialize::<P, D>(deserializer).map(Some)
+	}
+}
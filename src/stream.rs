@@ -0,0 +1,99 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use chrono::{DateTime, Duration, TimeZone};
+use futures_core::Stream;
+use tokio::time::{Instant, Sleep};
+
+use crate::errors::StringifyError;
+use crate::options::{DisplayConfig, Text};
+use crate::{countdown_with_date, Countdown};
+
+/// A `Stream` of the text for the interval from now to a reference instant, which yields only when the text changes. It counts down towards an instant in the future, like for the time remaining, and up from one in the past, like for uptime.
+///
+/// The time is kept by tokio's clock, so it can be tested with paused time. The stream can be made outside a tokio runtime, but has to be polled inside one with the time driver enabled. It ends after an error, or once the text can't change anymore.
+pub struct IntervalStream<Tz: TimeZone> {
+	instant: Instant,
+	/// The date at `instant`, which calendar units are counted from as it moves along with the clock.
+	date: DateTime<Tz>,
+	config: DisplayConfig,
+	text: Text,
+	/// The timer until the text changes, made on the first poll, so making the stream doesn't need a runtime.
+	sleep: Option<Pin<Box<Sleep>>>,
+	last: Option<String>,
+	ended: bool,
+}
+
+impl<Tz: TimeZone> IntervalStream<Tz> {
+	/// The first text is yielded right away. `date` is the date at `instant`, for calendar units.
+	pub fn new(instant: Instant, date: DateTime<Tz>, config: DisplayConfig, text: Text) -> Self {
+		Self {
+			instant,
+			date,
+			config,
+			text,
+			sleep: None,
+			last: None,
+			ended: false,
+		}
+	}
+	/// The interval from now to the instant, and the date now.
+	fn now(&self) -> Option<(Instant, Duration, DateTime<Tz>)> {
+		let now = Instant::now();
+		let interval = if now <= self.instant {
+			Duration::from_std(self.instant - now).ok()?
+		} else {
+			-Duration::from_std(now - self.instant).ok()?
+		};
+		let date = self.date.clone().checked_sub_signed(interval)?;
+		Some((now, interval, date))
+	}
+}
+
+// Nothing is pinned structurally, since the timer is boxed.
+impl<Tz: TimeZone> Unpin for IntervalStream<Tz> {}
+
+impl<Tz: TimeZone + 'static> Stream for IntervalStream<Tz> {
+	type Item = Result<String, StringifyError>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = self.get_mut();
+		loop {
+			if this.ended {
+				return Poll::Ready(None);
+			}
+			if let Some(sleep) = &mut this.sleep {
+				if sleep.as_mut().poll(cx).is_pending() {
+					return Poll::Pending;
+				}
+			}
+			let Some((now, interval, date)) = this.now() else {
+				this.ended = true;
+				return Poll::Ready(None);
+			};
+			let Countdown { text, next_change } =
+				match countdown_with_date(interval, date, &this.config, &this.text) {
+					Ok(countdown) => countdown,
+					Err(error) => {
+						this.ended = true;
+						return Poll::Ready(Some(Err(error)));
+					}
+				};
+			match next_change
+				.and_then(|next_change| next_change.to_std().ok())
+				.and_then(|next_change| now.checked_add(next_change))
+			{
+				Some(deadline) => match &mut this.sleep {
+					Some(sleep) => sleep.as_mut().reset(deadline),
+					None => this.sleep = Some(Box::pin(tokio::time::sleep_until(deadline))),
+				},
+				None => this.ended = true,
+			}
+			if this.last.as_ref() != Some(&text) {
+				this.last = Some(text.clone());
+				return Poll::Ready(Some(Ok(text)));
+			}
+		}
+	}
+}
@@ -1,141 +1,369 @@
-use std::fmt::Write;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
 
-use chrono::{DateTime, Datelike, Duration, Months, Utc};
+use chrono::Duration;
 
+use crate::calendar::{Calendar, CalendarSplit, ReferenceDate, MONTHS_PER};
+use crate::countdown::Horizon;
 use crate::errors::StringifyError;
-use crate::options::{DisplayConfigRef, OptionalDisplaySettings, Text};
+use crate::options::{
+	DisplayConfigRef, DisplaySettings, GrammaticalContext, OptionalDisplaySettings, Text,
+};
 use crate::threshold_map::ThresholdMap;
-use crate::util::UnitValues;
+use crate::util::{Unit, UnitValues};
+use crate::work_calendar::{working_time, BusinessLengths, WorkCalendar};
+
+/// Where the text is written. A `String` makes room for all of it first.
+pub(crate) trait Output: fmt::Write {
+	fn reserve(&mut self, _additional: usize) {}
+}
+
+impl Output for String {
+	fn reserve(&mut self, additional: usize) {
+		String::reserve(self, additional);
+	}
+}
+
+/// Any other writer, which is written to as it is.
+pub(crate) struct Writer<'w, W: ?Sized>(pub(crate) &'w mut W);
+
+impl<W: fmt::Write + ?Sized> fmt::Write for Writer<'_, W> {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		self.0.write_str(s)
+	}
+	fn write_char(&mut self, c: char) -> fmt::Result {
+		self.0.write_char(c)
+	}
+}
+
+impl<W: fmt::Write + ?Sized> Output for Writer<'_, W> {}
 
 pub(crate) fn stringify_interval(
-	mut interval: Duration,
-	get_date: Option<Box<dyn FnOnce() -> DateTime<Utc>>>,
+	interval: Duration,
+	get_date: Option<Box<dyn FnOnce() -> ReferenceDate>>,
 	config: DisplayConfigRef,
 	text: &Text,
 ) -> Result<String, StringifyError> {
-	let in_past = interval.num_seconds() < 0;
-	interval = interval.abs();
+	stringify_with_horizon(interval, get_date, config, text).map(|(output, _)| output)
+}
 
-	let mut enabled = EnabledUnits::from_interval_and_display_config(interval, config);
+/// Like `stringify_interval`, but also returns how many whole seconds the interval can count down before the text may change.
+pub(crate) fn stringify_with_horizon(
+	interval: Duration,
+	get_date: Option<Box<dyn FnOnce() -> ReferenceDate>>,
+	config: DisplayConfigRef,
+	text: &Text,
+) -> Result<(String, u64), StringifyError> {
+	let mut output = String::new();
+	let steps = write_interval(interval, get_date, config, text, &mut output)?;
+	Ok((output, steps))
+}
 
-	let round_to_months_or_years =
-		if let Some(seconds) = enabled.round_to_smallest(interval.num_seconds() as u64) {
-			interval = Duration::seconds(seconds as i64);
-			false
-		} else {
-			true
-		};
+/// Writes the text, and returns the steps of its `Horizon`.
+pub(crate) fn write_interval<O: Output>(
+	interval: Duration,
+	get_date: Option<Box<dyn FnOnce() -> ReferenceDate>>,
+	config: DisplayConfigRef,
+	text: &Text,
+	output: &mut O,
+) -> Result<u64, StringifyError> {
+	let in_past = interval < Duration::zero();
+	let context = config.phrasing.context(in_past);
+	config.validate()?;
+	text.validate(context, config)?;
+
+	let date = if config.has_inconstant_enabled() {
+		Some(get_date.ok_or(StringifyError::MissingReferenceDate)?())
+	} else {
+		None
+	};
 
-	let mut counts = Counts(UnitValues::<u64>::default());
+	build_output(interval, date.as_ref(), config, text, output)
+		.map(|horizon| horizon.steps())
+		.map_err(|error| error.with_context(interval, date.map(|date| date.date)))
+}
 
-	if config.has_inconstant_enabled() {
-		let date = get_date.unwrap()();
-		let (years, months, remainder) =
-			get_years_months_remainder(date, interval, in_past, round_to_months_or_years, config)
-				.ok_or(StringifyError::NumberOutOfRange)?;
-		if let Some(years) = years {
-			enabled.0.years = true;
-			counts.0.years = years;
-		}
-		if let Some(months) = months {
-			enabled.0.months = true;
-			counts.0.months = months;
-		}
-		interval = remainder;
+/// Stringifies the working time in the interval from the reference date, with weeks and days as business weeks and days.
+pub(crate) fn stringify_business_time(
+	interval: Duration,
+	date: ReferenceDate,
+	calendar: &dyn WorkCalendar,
+	config: DisplayConfigRef,
+	text: &Text,
+) -> Result<String, StringifyError> {
+	let context = config.phrasing.context(interval < Duration::zero());
+	config.validate()?;
+	text.validate(context, config)?;
+	let config = DisplayConfigRef {
+		business: Some(BusinessLengths::new(calendar, config)?),
+		..config
 	};
 
-	counts.split_duration(interval, &enabled);
-	enabled.filter_zeroes(&counts, config)?;
+	let mut output = String::new();
+	date.date
+		.checked_add_signed(interval)
+		.and_then(|to| working_time(date.date, to, date.zone.as_ref(), calendar))
+		.ok_or(StringifyError::out_of_range(None))
+		.and_then(|working| build_output(working, None, config, text, &mut output))
+		.map_err(|error| error.with_context(interval, Some(date.date)))?;
+	Ok(output)
+}
+
+fn build_output<O: Output>(
+	interval: Duration,
+	date: Option<&ReferenceDate>,
+	config: DisplayConfigRef,
+	text: &Text,
+	output: &mut O,
+) -> Result<Horizon, StringifyError> {
+	let in_past = interval < Duration::zero();
+	// Working with the unsigned number of seconds avoids `Duration::abs` and lets rounding go past `Duration`'s limits.
+	let mut seconds = interval.num_seconds().unsigned_abs();
+	let context = config.phrasing.context(in_past);
+	let units = UnitSpec::list(config, text, context);
+	let mut horizon = Horizon::new(in_past, seconds);
+
+	let mut enabled = EnabledUnits::from_seconds(seconds, &units, &mut horizon);
+
+	let mut counts = Counts {
+		whole: vec![0; units.len()],
+		fraction: None,
+	};
+
+	if let Some(date) = date {
+		// Calendar units are taken out of the exact interval, and only the remainder is rounded, since months aren't whole weeks.
+		let round_to_calendar = enabled.smallest_constant(&units).is_none();
+		let interval = i64::try_from(seconds)
+			.ok()
+			.and_then(Duration::try_seconds)
+			.ok_or(StringifyError::out_of_range(None))?;
+		let calendar = Calendar::new(date, interval, in_past, config, enabled.day_step(&units))
+			.ok_or(StringifyError::out_of_range(None))?;
+		let mut split = calendar
+			.split(interval, round_to_calendar)
+			.ok_or(StringifyError::out_of_range(None))?;
+		calendar.limit_horizon(&split.edges, &mut horizon);
+		seconds = split
+			.remainder
+			.num_seconds()
+			.try_into()
+			.map_err(|_| StringifyError::out_of_range(None))?;
+		// What is left is rounded in `Counts::split_duration`, unless one more of the smallest calendar unit is closer.
+		if let Some(rounded) = enabled.round_to_smallest(seconds, &units, &mut horizon)? {
+			if split.next_is_closer(seconds, rounded, &mut horizon) {
+				split = split
+					.carried(&calendar)
+					.ok_or(StringifyError::out_of_range(None))?;
+				seconds = 0;
+			}
+		}
+		counts.split_calendar(&split, &mut enabled, &units);
+	}
+
+	counts.split_duration(seconds, &enabled, &units, &mut horizon)?;
+	enabled.filter_zeroes(&counts, &units)?;
 
 	let mut remaining_elements = enabled.count();
 
-	let mut output = String::with_capacity(calculate_output_length(
-		text,
-		&enabled,
-		&counts,
-		config,
-		remaining_elements,
-	));
+	output.reserve(
+		calculate_output_length(text, context, &enabled, &counts, &units, remaining_elements)
+			.ok_or(StringifyError::out_of_range(None))?,
+	);
+
+	let (phrase_before, phrase_after) = text
+		.context(context)
+		.map_or(("", ""), |context| context.split_phrase());
+	output
+		.write_str(phrase_before)
+		.map_err(|_| StringifyError::Write)?;
 
-	for (&count, labels, config) in counts
-		.0
+	for (index, (unit, &count)) in units
 		.iter()
-		.zip(text.iter_units())
-		.zip(config.iter())
+		.zip(counts.whole.iter())
 		.zip(enabled.0.iter())
-		.filter_map(|(((ct, t), cfg), e)| e.then_some((ct, t, cfg)))
+		.enumerate()
+		.filter_map(|(i, ((u, ct), e))| e.then_some((i, (u, ct))))
 	{
 		print_unit(
-			&mut output,
+			output,
 			count,
-			&text.spacer,
-			labels,
+			counts.fraction_of(index),
+			text,
+			unit.text,
 			text.get_joiner(remaining_elements),
-			config.unwrap().pad,
-		);
+			unit.settings.settings(&unit.unit)?.pad,
+		)
+		.map_err(|_| StringifyError::Write)?;
 		remaining_elements -= 1;
 	}
 
-	Ok(output)
+	output
+		.write_str(phrase_after)
+		.map_err(|_| StringifyError::Write)?;
+	Ok(horizon)
 }
 
-fn print_unit(
-	output: &mut String,
+fn print_unit<O: Output>(
+	output: &mut O,
 	count: u64,
-	spacer: &str,
+	fraction: Option<Fraction>,
+	text: &Text,
 	label: &ThresholdMap<String>,
 	joiner: &str,
 	pad: u8,
-) {
-	write!(
-		output,
-		"{:0pad$}{}{}{}",
-		count,
-		spacer,
-		label.get(count),
-		joiner,
-		pad = pad as usize
-	)
-	.unwrap();
+) -> fmt::Result {
+	let (before, spacer, after) =
+		Text::split_template(label.get_fractional(count, fraction.is_some()));
+	output.write_str(before)?;
+	text.number_format.write_integer(output, count, pad)?;
+	if let Some(fraction) = fraction {
+		text.number_format
+			.write_fraction(output, fraction.digits, fraction.decimals)?;
+	}
+	output.write_str(spacer)?;
+	output.write_str(after)?;
+	output.write_str(joiner)
+}
+
+/// One of the units the interval may be split into, with its settings and text.
+struct UnitSpec<'l> {
+	unit: Unit,
+	/// The length of a constant unit in seconds, or 0 for a calendar unit. Weeks and days keep their usual length even when counted in calendar days.
+	seconds: u64,
+	/// The days in a unit counted in calendar days, or 0 if it isn't.
+	calendar_days: u64,
+	settings: &'l Option<DisplaySettings>,
+	text: &'l ThresholdMap<String>,
+}
+
+impl<'l> UnitSpec<'l> {
+	/// The calendar units, then the constant units: the built-in ones, or those listed in the config in their order.
+	fn list(
+		config: DisplayConfigRef<'l>,
+		text: &'l Text,
+		context: GrammaticalContext,
+	) -> Vec<Self> {
+		let built_in = config
+			.iter()
+			.zip(text.iter_units(context))
+			.zip(SECONDS_PER.iter())
+			.zip(Unit::ALL)
+			.take(config.built_in_count())
+			.map(|(((settings, text), &seconds), unit)| {
+				Self::new(unit, seconds, settings, text, config)
+			});
+		// Lengths were checked to be at least a second in `DisplayConfigRef::validate`.
+		let listed = config.constant_units.into_iter().flatten().map(|unit| {
+			Self::new(
+				unit.unit(),
+				unit.length.num_seconds().unsigned_abs(),
+				&unit.settings,
+				unit.labels.get(context),
+				config,
+			)
+		});
+		built_in.chain(listed).collect()
+	}
+	/// Weeks and days take their length from the work calendar when counting business time, and are counted in calendar days when asked to.
+	fn new(
+		unit: Unit,
+		seconds: u64,
+		settings: &'l Option<DisplaySettings>,
+		text: &'l ThresholdMap<String>,
+		config: DisplayConfigRef,
+	) -> Self {
+		Self {
+			seconds: match (&unit, config.business) {
+				(Unit::Weeks, Some(business)) => business.week.unwrap_or(seconds),
+				(Unit::Days, Some(business)) => business.day.unwrap_or(seconds),
+				_ => seconds,
+			},
+			calendar_days: match unit {
+				Unit::Weeks if config.calendar_days => 7,
+				Unit::Days if config.calendar_days => 1,
+				_ => 0,
+			},
+			unit,
+			settings,
+			text,
+		}
+	}
+	/// Whether the unit has a fixed length, so it isn't counted with the calendar.
+	fn is_constant(&self) -> bool {
+		self.seconds > 0 && self.calendar_days == 0
+	}
 }
 
 #[derive(Debug)]
-struct EnabledUnits(UnitValues<bool>);
+struct EnabledUnits(Vec<bool>);
 
 impl EnabledUnits {
-	fn from_interval_and_display_config(interval: Duration, config: DisplayConfigRef) -> Self {
-		Self(UnitValues {
-			years: false,
-			months: false,
-			weeks: config.weeks.range_contains(interval.num_weeks() as u64),
-			days: config.days.range_contains(interval.num_days() as u64),
-			hours: config.hours.range_contains(interval.num_hours() as u64),
-			minutes: config.minutes.range_contains(interval.num_minutes() as u64),
-			seconds: config.seconds.range_contains(interval.num_seconds() as u64),
-		})
+	/// Which constant units, and units counted in calendar days, should be displayed for the interval. Calendar units based on months are enabled later, by `Counts::split_calendar`.
+	fn from_seconds(seconds: u64, units: &[UnitSpec], horizon: &mut Horizon) -> Self {
+		for unit in units {
+			unit.settings.limit_horizon(unit.seconds, seconds, horizon);
+		}
+		Self(
+			units
+				.iter()
+				.map(|unit| {
+					unit.seconds > 0
+						&& unit
+							.settings
+							.range_contains(seconds / unit.seconds, seconds)
+				})
+				.collect(),
+		)
 	}
-	fn round_to_smallest(&self, seconds: u64) -> Option<u64> {
-		let rounded = if self.0.seconds {
-			seconds
-		} else if self.0.minutes {
-			round_to_nearest_multiple(seconds, SECONDS_PER.minutes)
-		} else if self.0.hours {
-			round_to_nearest_multiple(seconds, SECONDS_PER.hours)
-		} else if self.0.days {
-			round_to_nearest_multiple(seconds, SECONDS_PER.days)
-		} else if self.0.weeks {
-			round_to_nearest_multiple(seconds, SECONDS_PER.weeks)
-		} else {
-			return None;
+	/// The index and the unit of the smallest enabled constant unit.
+	fn smallest_constant<'u, 'l>(
+		&self,
+		units: &'u [UnitSpec<'l>],
+	) -> Option<(usize, &'u UnitSpec<'l>)> {
+		self.0
+			.iter()
+			.zip(units)
+			.enumerate()
+			.rev()
+			.find_map(|(i, (e, u))| (*e && u.is_constant()).then_some((i, u)))
+	}
+	/// The days in the smallest enabled unit counted in calendar days, or 0 if there is none.
+	fn day_step(&self, units: &[UnitSpec]) -> u64 {
+		self.0
+			.iter()
+			.zip(units)
+			.rev()
+			.find_map(|(e, u)| (*e && u.calendar_days > 0).then_some(u.calendar_days))
+			.unwrap_or(0)
+	}
+	/// Rounds to the nearest multiple of the smallest enabled constant unit, or of its last decimal place if it has decimals, to the second. Returns `None` if there is no such unit.
+	fn round_to_smallest(
+		&self,
+		seconds: u64,
+		units: &[UnitSpec],
+		horizon: &mut Horizon,
+	) -> Result<Option<u64>, StringifyError> {
+		let Some((_, unit)) = self.smallest_constant(units) else {
+			return Ok(None);
 		};
-		Some(rounded)
+		let out_of_range = || StringifyError::out_of_range(Some(unit.unit.clone()));
+		let decimals = unit.settings.settings(&unit.unit)?.decimals;
+		let scale = 10_u64
+			.checked_pow(decimals as u32)
+			.ok_or_else(out_of_range)? as u128;
+		let seconds_per = unit.seconds as u128;
+		// In steps of a `scale`th of the unit, the same as `Counts::split_duration` rounds to, and then back in seconds.
+		let steps = (seconds as u128 * scale + seconds_per / 2) / seconds_per;
+		limit_rounding(seconds, steps, scale, seconds_per, horizon);
+		u64::try_from((steps * seconds_per + scale / 2) / scale)
+			.map(Some)
+			.map_err(|_| out_of_range())
 	}
 	/// Sets all the units disabled that have 0 values and shouldn't display at 0. If that would disable all units, leave the smallest enabled.
-	fn filter_zeroes(
-		&mut self,
-		counts: &Counts,
-		config: DisplayConfigRef,
-	) -> Result<(), StringifyError> {
+	fn filter_zeroes(&mut self, counts: &Counts, units: &[UnitSpec]) -> Result<(), StringifyError> {
 		let smallest_enabled = self
 			.0
 			.iter()
@@ -146,21 +374,26 @@ impl EnabledUnits {
 
 		let mut any_enabled = false;
 
-		for (enabled, count, config) in self
+		for (index, (enabled, count, unit)) in self
 			.0
 			.iter_mut()
-			.zip(counts.0.iter())
-			.zip(config.iter())
-			.filter_map(|((e, ct), cfg)| e.then_some((e, ct, cfg)))
+			.zip(counts.whole.iter())
+			.zip(units)
+			.enumerate()
+			.filter_map(|(i, ((e, ct), u))| e.then_some((i, (e, ct, u))))
 		{
-			*enabled = *count > 0 || config.unwrap().display_zero;
+			*enabled = *count > 0
+				|| counts.fraction_of(index).is_some()
+				|| unit.settings.settings(&unit.unit)?.display_zero;
 			if *enabled {
 				any_enabled = true;
 			}
 		}
 
 		if !any_enabled {
-			*self.0.iter_mut().nth(smallest_enabled).unwrap() = true;
+			if let Some(smallest) = self.0.get_mut(smallest_enabled) {
+				*smallest = true;
+			}
 		}
 
 		Ok(())
@@ -170,31 +403,199 @@ impl EnabledUnits {
 	}
 }
 
-struct Counts(UnitValues<u64>);
+struct Counts {
+	/// The count of each unit, in the order of `UnitSpec::list`.
+	whole: Vec<u64>,
+	/// The fractional part of the smallest constant unit, if it has decimals and the fraction isn't zero.
+	fraction: Option<Fraction>,
+}
+
+/// The digits after the decimal separator, with trailing zeroes removed.
+#[derive(Debug, Clone, Copy)]
+struct Fraction {
+	unit: usize,
+	digits: u64,
+	decimals: u8,
+}
 
 impl Counts {
 	/// Allocate the remaining duration across the enabled constant units.
-	fn split_duration(&mut self, interval: Duration, enabled: &EnabledUnits) {
-		let mut seconds = interval.num_seconds() as u64;
-		for (count, seconds_per) in self
+	///
+	/// The smallest of them takes what is left of the duration rounded to the nearest, to as many decimal places as it has, and what doesn't add up to a whole unit becomes the fraction. When the next larger unit isn't a whole number of the smallest, one more of it may be closer than that, and then it is used instead.
+	fn split_duration(
+		&mut self,
+		seconds: u64,
+		enabled: &EnabledUnits,
+		units: &[UnitSpec],
+		horizon: &mut Horizon,
+	) -> Result<(), StringifyError> {
+		let Some((smallest, unit)) = enabled.smallest_constant(units) else {
+			// Nothing to split across. Any remainder was rounded away with the calendar units, or nothing is displayed at all.
+			return Ok(());
+		};
+		let out_of_range = StringifyError::out_of_range(Some(unit.unit.clone()));
+		let decimals = unit.settings.settings(&unit.unit)?.decimals;
+		let scale = 10_u64
+			.checked_pow(decimals as u32)
+			.ok_or_else(|| out_of_range.clone())? as u128;
+		let seconds_per = unit.seconds as u128;
+		// The rest, in units of a `scale`th of the smallest unit.
+		let scaled_rest = |rest: u64| (rest as u128 * scale + seconds_per / 2) / seconds_per;
+
+		let (mut rest, larger) =
+			self.split_larger(seconds, smallest, enabled, units, Some(horizon));
+		let mut scaled = scaled_rest(rest);
+		limit_rounding(rest, scaled, scale, seconds_per, horizon);
+		if let Some(larger) = larger {
+			// Past `scaled`, one more of the larger unit is as close as the rounded rest halfway between them.
+			let halfway = (larger as u128 * scale + scaled * seconds_per).div_ceil(2 * scale);
+			if let Ok(halfway) = u64::try_from(halfway) {
+				horizon.edge(rest, halfway);
+			}
+			let exact = rest as u128 * scale;
+			if (larger as u128 * scale).abs_diff(exact) <= (scaled * seconds_per).abs_diff(exact) {
+				let carried = (seconds - rest)
+					.checked_add(larger)
+					.ok_or_else(|| out_of_range.clone())?;
+				(rest, _) = self.split_larger(carried, smallest, enabled, units, None);
+				scaled = scaled_rest(rest);
+			}
+		}
+
+		self.whole[smallest] = (scaled / scale).try_into().map_err(|_| out_of_range)?;
+		// Both are less than `scale`, which is a `u64`.
+		self.fraction = Fraction::new(smallest, (scaled % scale) as u64, decimals);
+
+		// The units should add back up to what was split, give or take rounding the smallest unit and maybe carrying into the next larger one, which are each off by at most half of the smallest unit.
+		let split_seconds = self
+			.whole
+			.iter()
+			.zip(units)
+			.zip(enabled.0.iter())
+			.take(smallest)
+			.filter(|((_, u), e)| **e && u.is_constant())
+			.fold(scaled * seconds_per / scale, |total, ((&count, u), _)| {
+				total + count as u128 * u.seconds as u128
+			});
+		if split_seconds.abs_diff(seconds as u128) > seconds_per {
+			return Err(StringifyError::InternalRounding {
+				interval: Duration::zero(),
+			});
+		}
+		Ok(())
+	}
+	/// Sets the whole counts of the enabled constant units larger than the smallest. Returns what is left, and the length of the smallest of those units, if any. Limits the horizon, if given, to when any of the counts change.
+	fn split_larger(
+		&mut self,
+		mut seconds: u64,
+		smallest: usize,
+		enabled: &EnabledUnits,
+		units: &[UnitSpec],
+		mut horizon: Option<&mut Horizon>,
+	) -> (u64, Option<u64>) {
+		let mut larger = None;
+		for (count, unit) in self
+			.whole
+			.iter_mut()
+			.zip(units)
+			.zip(enabled.0.iter())
+			.take(smallest)
+			.filter_map(|((ct, u), e)| (*e && u.is_constant()).then_some((ct, u)))
+		{
+			*count = seconds / unit.seconds;
+			if let Some(horizon) = horizon.as_deref_mut() {
+				let whole = seconds - seconds % unit.seconds;
+				horizon.edge(seconds, whole);
+				horizon.edge(seconds, whole.saturating_add(unit.seconds));
+			}
+			seconds %= unit.seconds;
+			larger = Some(unit.seconds);
+		}
+		(seconds, larger)
+	}
+	/// Splits the months and days taken out by the calendar across the displayed calendar units, from the largest down.
+	fn split_calendar(
+		&mut self,
+		split: &CalendarSplit,
+		enabled: &mut EnabledUnits,
+		units: &[UnitSpec],
+	) {
+		let mut months = split.months;
+		for (((enabled, count), &split_enabled), &months_per) in enabled
 			.0
 			.iter_mut()
-			.zip(SECONDS_PER.iter())
+			.zip(self.whole.iter_mut())
+			.zip(split.enabled.iter())
+			.zip(MONTHS_PER.iter())
+			.take(Unit::CALENDAR_COUNT)
+		{
+			if split_enabled {
+				*enabled = true;
+				*count = u64::from(months / months_per);
+				months %= months_per;
+			}
+		}
+		let mut days = split.days;
+		for ((count, unit), _) in self
+			.whole
+			.iter_mut()
+			.zip(units)
 			.zip(enabled.0.iter())
-			.skip(2)
-			.filter_map(|(v, enabled)| enabled.then_some(v))
+			.filter(|((_, u), e)| **e && u.calendar_days > 0)
 		{
-			*count += seconds / seconds_per;
-			seconds %= seconds_per;
+			*count = days / unit.calendar_days;
+			days %= unit.calendar_days;
 		}
-		if seconds != 0 {
-			eprintln!("Something went wrong with rounding.");
+	}
+	fn fraction_of(&self, unit: usize) -> Option<Fraction> {
+		self.fraction.filter(|fraction| fraction.unit == unit)
+	}
+}
+
+impl Fraction {
+	/// Trims trailing zeroes, and returns `None` if nothing is left.
+	fn new(unit: usize, mut digits: u64, mut decimals: u8) -> Option<Self> {
+		if digits == 0 {
+			return None;
+		}
+		while digits.is_multiple_of(10) {
+			digits /= 10;
+			decimals -= 1;
+		}
+		Some(Self {
+			unit,
+			digits,
+			decimals,
+		})
+	}
+}
+
+/// Limits the horizon to when `seconds`, rounded to the nearest `scale`th of a unit `seconds_per` long, is something other than `scaled` of them.
+fn limit_rounding(
+	seconds: u64,
+	scaled: u128,
+	scale: u128,
+	seconds_per: u128,
+	horizon: &mut Horizon,
+) {
+	// Rounding to the nearest adds half a unit and rounds down, so each step starts half a unit before it.
+	let start = |scaled: u128| {
+		(scaled * seconds_per)
+			.saturating_sub(seconds_per / 2)
+			.div_ceil(scale)
+	};
+	for edge in [start(scaled), start(scaled + 1)] {
+		if let Ok(edge) = u64::try_from(edge) {
+			horizon.edge(seconds, edge);
 		}
 	}
 }
 
-const SECONDS_PER: UnitValues<u64> = UnitValues {
+pub(crate) const SECONDS_PER: UnitValues<u64> = UnitValues {
+	centuries: 0,
+	decades: 0,
 	years: 0,
+	quarters: 0,
 	months: 0,
 	weeks: 7 * 24 * 60 * 60,
 	days: 24 * 60 * 60,
@@ -203,195 +604,43 @@ const SECONDS_PER: UnitValues<u64> = UnitValues {
 	seconds: 1,
 };
 
-fn round_to_nearest_multiple(n: u64, m: u64) -> u64 {
-	(n + m / 2) / m * m
-}
-
-fn get_years_months_remainder(
-	start_date: DateTime<Utc>,
-	interval: Duration,
-	in_past: bool,
-	should_round: bool,
-	config: DisplayConfigRef,
-) -> Option<(Option<u64>, Option<u64>, Duration)> {
-	let target_date = if in_past {
-		start_date.checked_sub_signed(interval)?
-	} else {
-		start_date.checked_add_signed(interval)?
-	};
-
-	let (larger_date, smaller_date) = if in_past {
-		(start_date, target_date)
-	} else {
-		(target_date, start_date)
-	};
-	let mut months = ((larger_date.year() - smaller_date.year()) * 12 + larger_date.month() as i32
-		- smaller_date.month() as i32) as u32;
-
-	let adjusted_date = if in_past {
-		let new_date = start_date.checked_sub_months(Months::new(months))?;
-		if new_date < target_date {
-			// Went too far
-			months -= 1;
-			start_date.checked_sub_months(Months::new(months))?
-		} else {
-			new_date
-		}
-	} else {
-		let new_date = start_date.checked_add_months(Months::new(months))?;
-		if new_date > target_date {
-			// Went too far
-			months -= 1;
-			start_date.checked_add_months(Months::new(months))?
-		} else {
-			new_date
-		}
-	};
-
-	let years = months / 12;
-
-	let enable_years = config.years.range_contains(years);
-	let enable_months = config.months.range_contains(months);
-
-	let (mut output_years, mut output_months, mut output_remainder) = (None, None, interval);
-
-	match (should_round, enable_years, enable_months) {
-		(_, false, false) => (), // Neither ends up displayed
-		(false, true, false) => {
-			// Only years
-			let months = Months::new(years * 12);
-			let adjusted_date = if in_past {
-				start_date.checked_sub_months(months)?
-			} else {
-				start_date.checked_add_months(months)?
-			};
-			let remaining_interval = interval - (start_date - adjusted_date).abs();
-			output_years = Some(years as u64);
-			output_remainder = remaining_interval;
-		}
-		(false, false, true) => {
-			// Only months
-			let remaining_interval = interval - (start_date - adjusted_date).abs();
-			output_months = Some(months as u64);
-			output_remainder = remaining_interval;
-		}
-		(false, true, true) => {
-			// Both years and months
-			let remaining_interval = interval - (start_date - adjusted_date).abs();
-			output_years = Some(years as u64);
-			output_months = Some(months as u64 % 12);
-			output_remainder = remaining_interval;
-		}
-		(true, true, false) => {
-			// Only years and round to them
-			let mut years: u32 = (larger_date.year() - smaller_date.year()).try_into().ok()?;
-			let adjusted_date = if in_past {
-				let new_date = start_date.checked_sub_months(Months::new(years * 12))?;
-				if new_date < target_date {
-					// Went too far
-					years -= 1;
-					start_date.checked_sub_months(Months::new(years * 12))?
-				} else {
-					new_date
-				}
-			} else {
-				let new_date = start_date.checked_add_months(Months::new(years * 12))?;
-				if new_date > target_date {
-					// Went too far
-					years -= 1;
-					start_date.checked_add_months(Months::new(years * 12))?
-				} else {
-					new_date
-				}
-			};
-			if is_one_year_further_closer(target_date, adjusted_date, in_past)? {
-				years += 1;
-			}
-			output_years = Some(years as u64);
-			output_remainder = Duration::zero();
-		}
-		(true, false, true) => {
-			// Only months and round to them
-			if is_one_month_further_closer(target_date, adjusted_date, in_past)? {
-				months += 1;
-			}
-			output_months = Some(months as u64);
-			output_remainder = Duration::zero();
-		}
-		(true, true, true) => {
-			// Years and months and round to months
-			if is_one_month_further_closer(target_date, adjusted_date, in_past)? {
-				months += 1;
-			}
-			output_years = Some(months as u64 / 12);
-			output_months = Some(months as u64 % 12);
-			output_remainder = Duration::zero();
-		}
-	};
-
-	Some((output_years, output_months, output_remainder))
-}
-
-fn is_n_months_further_closer(
-	target_date: DateTime<Utc>,
-	date_before: DateTime<Utc>,
-	in_past: bool,
-	n: u32,
-) -> Option<bool> {
-	let n_months_further = if in_past {
-		date_before.checked_sub_months(Months::new(n))?
-	} else {
-		date_before.checked_add_months(Months::new(n))?
-	};
-	Some((target_date - n_months_further).abs() <= (target_date - date_before).abs())
-}
-
-fn is_one_month_further_closer(
-	target_date: DateTime<Utc>,
-	date_before: DateTime<Utc>,
-	in_past: bool,
-) -> Option<bool> {
-	is_n_months_further_closer(target_date, date_before, in_past, 1)
-}
-
-fn is_one_year_further_closer(
-	target_date: DateTime<Utc>,
-	date_before: DateTime<Utc>,
-	in_past: bool,
-) -> Option<bool> {
-	is_n_months_further_closer(target_date, date_before, in_past, 12)
-}
-
 fn calculate_output_length(
 	text: &Text,
+	context: GrammaticalContext,
 	enabled: &EnabledUnits,
 	counts: &Counts,
-	config: DisplayConfigRef,
+	units: &[UnitSpec],
 	element_count: usize,
-) -> usize {
-	let mut length = text.spacer.len() * element_count;
+) -> Option<usize> {
+	let mut length = text
+		.context(context)
+		.map_or(0, |context| context.phrase.len());
 	if element_count > 1 {
-		length += text.joiner.len() * (element_count - 2);
-		length += text
-			.final_joiner
-			.as_deref()
-			.map_or(text.joiner.len(), |joiner| joiner.len());
+		length = length.checked_add(text.joiner.len().checked_mul(element_count - 2)?)?;
+		length = length.checked_add(
+			text.final_joiner
+				.as_deref()
+				.map_or(text.joiner.len(), |joiner| joiner.len()),
+		)?;
 	}
-	for (text, &count, config) in enabled
-		.0
+	let number_format = &text.number_format;
+	for (index, (unit, &count)) in units
 		.iter()
-		.zip(text.iter_units())
-		.zip(counts.0.iter())
-		.zip(config.iter())
-		.filter_map(|(((e, t), ct), cfg)| e.then_some((t, ct, cfg)))
+		.zip(counts.whole.iter())
+		.zip(enabled.0.iter())
+		.enumerate()
+		.filter_map(|(i, ((u, ct), e))| e.then_some((i, (u, ct))))
 	{
-		length += text.get(count).len();
-		length += if count == 0 {
-			1
-		} else {
-			count.ilog10() as usize + 1
+		let fraction = counts.fraction_of(index);
+		if let Some(fraction) = fraction {
+			length = length.checked_add(number_format.fraction_len(fraction.digits, fraction.decimals))?;
 		}
-		.max(config.unwrap().pad as usize);
+		let (before, spacer, after) =
+			Text::split_template(unit.text.get_fractional(count, fraction.is_some()));
+		length = length.checked_add(before.len() + spacer.len() + after.len())?;
+		// Missing settings would already have been caught in `EnabledUnits::filter_zeroes`.
+		let pad = unit.settings.map_or(0, |config| config.pad);
+		length = length.checked_add(number_format.integer_len(count, pad))?;
 	}
-	length
+	Some(length)
 }
@@ -1,11 +1,25 @@
+#[allow(clippy::module_inception)]
 #[cfg(test)]
 mod tests {
-	use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+	use std::fmt;
+
+	use chrono::{
+		DateTime, Datelike, Duration, FixedOffset, LocalResult, NaiveDate, NaiveDateTime,
+		NaiveTime, TimeZone, Utc,
+	};
 
 	use crate::{
-		options::{DisplayConfig, DisplayConfigConstant, DisplaySettings, Text},
+		business_time, countdown_with_date, countdown_without_date,
+		errors::{ErrorKind, ParseError, StringifyError},
+		options::{
+			Anchor, ConstantUnit, ContextText, DisplayConfig, DisplayConfigConstant, DisplayRange,
+			DisplaySettings, MonthArithmetic, Phrasing, Text,
+		},
+		parse, relative_date,
 		threshold_map::ThresholdMap,
-		with_date, without_date,
+		util::Unit,
+		with_date, without_date, write_without_date, NumberFormat, RelativeText, WorkCalendar,
+		WorkWeek,
 	};
 
 	fn date_year_month_day(year: i32, month: u32, day: u32) -> DateTime<Utc> {
@@ -126,6 +140,7 @@ mod tests {
 			hours: None,
 			minutes: Some(DisplaySettings::new(0.., 0, false)),
 			seconds: Some(DisplaySettings::new(0.., 0, false)),
+			..DisplayConfigConstant::none()
 		}
 	}
 	#[test]
@@ -275,6 +290,7 @@ mod tests {
 			hours: Some(DisplaySettings::new(0.., 0, true)),
 			minutes: Some(DisplaySettings::new(0.., 0, true)),
 			seconds: Some(DisplaySettings::new(0..600, 0, true)),
+			..DisplayConfig::none()
 		}
 	}
 	#[test]
@@ -313,14 +329,15 @@ mod tests {
 			hours: Some(DisplaySettings::new(0.., 2, true)),
 			minutes: Some(DisplaySettings::new(0.., 2, true)),
 			seconds: Some(DisplaySettings::new(0.., 2, true)),
+			..DisplayConfigConstant::none()
 		}
 	}
+	#[allow(clippy::field_reassign_with_default)]
 	fn text_clocklike() -> Text {
 		let mut text = Text::default();
-		text.hours = ThresholdMap::single_value("");
-		text.minutes = ThresholdMap::single_value("");
-		text.seconds = ThresholdMap::single_value("");
-		text.spacer = String::from("");
+		text.hours = ThresholdMap::single_value("{n}");
+		text.minutes = ThresholdMap::single_value("{n}");
+		text.seconds = ThresholdMap::single_value("{n}");
 		text.joiner = String::from(":");
 		text.final_joiner = None;
 		text
@@ -344,6 +361,2535 @@ mod tests {
 		)
 	}
 
+	fn config_fractional_hours() -> DisplayConfigConstant {
+		DisplayConfigConstant {
+			weeks: None,
+			days: Some(DisplaySettings::new(0.., 0, false)),
+			hours: Some(DisplaySettings::new(0.., 0, false).with_decimals(2)),
+			minutes: None,
+			seconds: None,
+			..DisplayConfigConstant::none()
+		}
+	}
+	#[test]
+	fn fractional_hours() {
+		assert_eq!(
+			without_date(
+				Duration::hours(3) + Duration::minutes(30),
+				&config_fractional_hours(),
+				&Text::default(),
+			),
+			Ok(String::from("3.5 hours"))
+		)
+	}
+	#[test]
+	fn fractional_hours_rounded() {
+		assert_eq!(
+			without_date(
+				Duration::days(2) + Duration::hours(1) + Duration::seconds(50),
+				&config_fractional_hours(),
+				&Text::default(),
+			),
+			Ok(String::from("2 days and 1.01 hours"))
+		)
+	}
+	#[test]
+	fn fractional_hours_carry() {
+		assert_eq!(
+			without_date(
+				Duration::days(1) - Duration::seconds(10),
+				&config_fractional_hours(),
+				&Text::default(),
+			),
+			Ok(String::from("1 day"))
+		)
+	}
+	#[test]
+	fn fractional_hours_below_one() {
+		assert_eq!(
+			without_date(
+				Duration::days(1) + Duration::minutes(45),
+				&config_fractional_hours(),
+				&Text {
+					number_format: NumberFormat {
+						decimal_separator: String::from(","),
+						..Default::default()
+					},
+					..Default::default()
+				},
+			),
+			Ok(String::from("1 day and 0,75 hours"))
+		)
+	}
+	#[test]
+	fn fractional_singular() {
+		assert_eq!(
+			without_date(
+				Duration::hours(1),
+				&config_fractional_hours(),
+				&Text::default(),
+			),
+			Ok(String::from("1 hour"))
+		)
+	}
+	#[test]
+	fn fractional_plural_by_default() {
+		let text = Text {
+			hours: ThresholdMap::from_iter("{n} hours", [(1, "{n} hour"), (2, "{n} hours")])
+				.unwrap(),
+			..Default::default()
+		};
+		assert_eq!(
+			without_date(Duration::minutes(90), &config_fractional_hours(), &text),
+			Ok(String::from("1.5 hours"))
+		);
+		assert_eq!(
+			without_date(Duration::minutes(60), &config_fractional_hours(), &text),
+			Ok(String::from("1 hour"))
+		);
+	}
+	#[test]
+	fn fractional_by_thresholds() {
+		// French takes the singular below 2, with or without decimals.
+		let heures = ThresholdMap::from_iter("{n} heure", [(2, "{n} heures")]).unwrap();
+		let text = Text {
+			hours: heures.clone().with_fractional_thresholds(heures),
+			number_format: NumberFormat {
+				decimal_separator: String::from(","),
+				..Default::default()
+			},
+			..Default::default()
+		};
+		let config = DisplayConfigConstant {
+			hours: Some(DisplaySettings::new(0.., 0, false).with_decimals(2)),
+			..DisplayConfigConstant::none()
+		};
+		let fractional = |minutes| without_date(Duration::minutes(minutes), &config, &text);
+		assert_eq!(fractional(30), Ok(String::from("0,5 heure")));
+		assert_eq!(fractional(90), Ok(String::from("1,5 heure")));
+		assert_eq!(fractional(150), Ok(String::from("2,5 heures")));
+		assert_eq!(fractional(60), Ok(String::from("1 heure")));
+	}
+	#[test]
+	fn fractional_carry_into_calendar() {
+		let date = date_year_month_day(2001, 1, 1);
+		for decimals in [0, 1] {
+			let config = DisplayConfig {
+				hours: Some(DisplaySettings::new(0.., 0, false).with_decimals(decimals)),
+				..DisplayConfig::none().with_years()
+			};
+			assert_eq!(
+				with_date(
+					Duration::days(365) - Duration::minutes(1),
+					date,
+					&config,
+					&Text::default()
+				),
+				Ok(String::from("1 year"))
+			);
+			let config = DisplayConfig {
+				days: Some(DisplaySettings::new(0.., 0, false).with_decimals(decimals)),
+				..DisplayConfig::none().with_months()
+			};
+			assert_eq!(
+				with_date(
+					Duration::days(58) + Duration::hours(23),
+					date,
+					&config,
+					&Text::default()
+				),
+				Ok(String::from("2 months"))
+			);
+		}
+	}
+
+	#[test]
+	fn grouped_digits() {
+		assert_eq!(
+			without_date(
+				Duration::seconds(-5_000_000),
+				&config_weeks_seconds(),
+				&Text {
+					number_format: NumberFormat {
+						grouping_separator: Some(String::from(" ")),
+						..Default::default()
+					},
+					..Default::default()
+				},
+			),
+			Ok(String::from("8 weeks and 161 600 seconds"))
+		);
+	}
+	#[test]
+	fn native_digits_padded() {
+		assert_eq!(
+			without_date(
+				Duration::hours(1) + Duration::minutes(5),
+				&config_clocklike(),
+				&Text {
+					number_format: NumberFormat {
+						digits: NumberFormat::ARABIC_INDIC_DIGITS,
+						..Default::default()
+					},
+					..text_clocklike()
+				},
+			),
+			Ok(String::from("٠١:٠٥:٠٠"))
+		);
+	}
+	#[test]
+	fn padding_not_grouped() {
+		let config = DisplayConfigConstant {
+			days: Some(DisplaySettings::new(0.., 6, false)),
+			..DisplayConfigConstant::none()
+		};
+		let text = Text {
+			number_format: NumberFormat {
+				grouping_separator: Some(String::from(",")),
+				..Default::default()
+			},
+			..Default::default()
+		};
+		assert_eq!(
+			without_date(Duration::days(5), &config, &text),
+			Ok(String::from("000005 days"))
+		);
+		assert_eq!(
+			without_date(Duration::days(1_234), &config, &text),
+			Ok(String::from("001,234 days"))
+		);
+	}
+	#[test]
+	fn native_digits_grouped() {
+		assert_eq!(
+			without_date(
+				Duration::days(1_000) + Duration::hours(12),
+				&DisplayConfigConstant::none().with_days().with_hours(),
+				&Text {
+					number_format: NumberFormat {
+						grouping_separator: Some(String::from(",")),
+						grouping_size: 2,
+						decimal_separator: String::from("."),
+						digits: NumberFormat::DEVANAGARI_DIGITS,
+					},
+					..Default::default()
+				},
+			),
+			Ok(String::from("१०,०० days and १२ hours"))
+		);
+	}
+	#[test]
+	fn mixed_width_digits_length() {
+		let number_format = NumberFormat {
+			grouping_separator: Some(String::from("٬")),
+			digits: ['0', '١', '२', '3', '4', '5', '6', '7', '8', '9'],
+			..Default::default()
+		};
+		let mut integer = String::new();
+		number_format.write_integer(&mut integer, 1_200, 6).unwrap();
+		assert_eq!(integer, "00١٬२00");
+		assert_eq!(number_format.integer_len(1_200, 6), integer.len());
+		let mut fraction = String::new();
+		number_format.write_fraction(&mut fraction, 12, 3).unwrap();
+		assert_eq!(fraction, ".0١२");
+		assert_eq!(number_format.fraction_len(12, 3), fraction.len());
+	}
+
+	fn text_compact() -> Text {
+		Text {
+			days: ThresholdMap::from_iter("{n} days", [(1, "{n} day"), (2, "{n} days")]).unwrap(),
+			hours: ThresholdMap::single_value("{n}h"),
+			minutes: ThresholdMap::single_value("{n}m"),
+			joiner: String::from(" "),
+			final_joiner: None,
+			..Default::default()
+		}
+	}
+	#[test]
+	fn templates() {
+		assert_eq!(
+			without_date(
+				Duration::seconds(500_000),
+				&DisplayConfigConstant::default(),
+				&text_compact(),
+			),
+			Ok(String::from("5 days 18h 53m"))
+		)
+	}
+	#[test]
+	fn template_label_first() {
+		assert_eq!(
+			without_date(
+				Duration::days(3),
+				&DisplayConfigConstant::default(),
+				&Text {
+					days: ThresholdMap::single_value("ngày {n}"),
+					..Default::default()
+				},
+			),
+			Ok(String::from("ngày 3"))
+		)
+	}
+
+	#[test]
+	fn relative_future() {
+		assert_eq!(
+			without_date(
+				Duration::days(3),
+				&DisplayConfigConstant::default().with_phrasing(Phrasing::Relative),
+				&Text::default(),
+			),
+			Ok(String::from("in 3 days"))
+		)
+	}
+	#[test]
+	fn relative_past() {
+		assert_eq!(
+			without_date(
+				Duration::seconds(-5_000),
+				&DisplayConfigConstant::default().with_phrasing(Phrasing::Relative),
+				&Text::default(),
+			),
+			Ok(String::from("1 hour and 23 minutes ago"))
+		)
+	}
+	#[test]
+	fn relative_past_below_one_second() {
+		assert_eq!(
+			without_date(
+				Duration::milliseconds(-500),
+				&DisplayConfigConstant::default().with_phrasing(Phrasing::Relative),
+				&Text::default(),
+			),
+			Ok(String::from("0 seconds ago"))
+		)
+	}
+
+	fn text_german() -> Text {
+		let dative_days = ThresholdMap::from_iter("{n} Tagen", [(1, "{n} Tag"), (2, "{n} Tagen")]);
+		let dative_hours =
+			ThresholdMap::from_iter("{n} Stunden", [(1, "{n} Stunde"), (2, "{n} Stunden")]);
+		Text {
+			days: ThresholdMap::from_iter("{n} Tage", [(1, "{n} Tag"), (2, "{n} Tage")]).unwrap(),
+			hours: dative_hours.clone().unwrap(),
+			final_joiner: Some(String::from(" und ")),
+			future: ContextText {
+				days: dative_days.clone(),
+				hours: dative_hours.clone(),
+				..ContextText::with_phrase("in {interval}")
+			},
+			past: ContextText {
+				days: dative_days,
+				hours: dative_hours,
+				..ContextText::with_phrase("vor {interval}")
+			},
+			duration: ContextText::with_phrase("{interval} lang"),
+			..Default::default()
+		}
+	}
+	#[test]
+	fn grammatical_context_standalone() {
+		assert_eq!(
+			without_date(
+				Duration::days(3) + Duration::hours(2),
+				&DisplayConfigConstant::none().with_days().with_hours(),
+				&text_german(),
+			),
+			Ok(String::from("3 Tage und 2 Stunden"))
+		)
+	}
+	#[test]
+	fn grammatical_context_future() {
+		assert_eq!(
+			without_date(
+				Duration::days(3) + Duration::hours(2),
+				&DisplayConfigConstant::none()
+					.with_days()
+					.with_hours()
+					.with_phrasing(Phrasing::Relative),
+				&text_german(),
+			),
+			Ok(String::from("in 3 Tagen und 2 Stunden"))
+		)
+	}
+	#[test]
+	fn grammatical_context_past() {
+		assert_eq!(
+			without_date(
+				-Duration::days(3),
+				&DisplayConfigConstant::none()
+					.with_days()
+					.with_hours()
+					.with_phrasing(Phrasing::Relative),
+				&text_german(),
+			),
+			Ok(String::from("vor 3 Tagen"))
+		)
+	}
+	#[test]
+	fn grammatical_context_duration() {
+		assert_eq!(
+			with_date(
+				Duration::days(3),
+				date_year_month_day(2020, 1, 1),
+				&DisplayConfig::none()
+					.with_days()
+					.with_phrasing(Phrasing::Duration),
+				&text_german(),
+			),
+			Ok(String::from("3 Tage lang"))
+		)
+	}
+
+	#[test]
+	fn min_duration() {
+		assert_eq!(
+			without_date(
+				Duration::MIN,
+				&DisplayConfigConstant::default(),
+				&Text::default(),
+			),
+			Ok(String::from("106751991167 days, 7 hours and 13 minutes"))
+		)
+	}
+	#[test]
+	fn max_duration_weeks() {
+		assert_eq!(
+			without_date(
+				Duration::MAX,
+				&DisplayConfigConstant::none().with_weeks(),
+				&Text::default(),
+			),
+			Ok(String::from("15250284452 weeks"))
+		)
+	}
+	#[test]
+	fn max_duration_with_date() {
+		assert_eq!(
+			with_date(
+				Duration::MAX,
+				date_year_month_day(2000, 1, 1),
+				&DisplayConfig::default(),
+				&Text::default(),
+			),
+			Err(StringifyError::NumberOutOfRange {
+				unit: None,
+				interval: Duration::MAX,
+				date: Some(date_year_month_day(2000, 1, 1)),
+			})
+		)
+	}
+	#[test]
+	fn past_max_date() {
+		assert_eq!(
+			with_date(
+				Duration::seconds(1),
+				DateTime::<Utc>::MAX_UTC,
+				&DisplayConfig::default(),
+				&Text::default(),
+			),
+			Err(StringifyError::NumberOutOfRange {
+				unit: None,
+				interval: Duration::seconds(1),
+				date: Some(DateTime::<Utc>::MAX_UTC),
+			})
+		)
+	}
+	#[test]
+	fn whole_date_range() {
+		assert_eq!(
+			with_date(
+				DateTime::<Utc>::MAX_UTC - DateTime::<Utc>::MIN_UTC,
+				DateTime::<Utc>::MIN_UTC,
+				&DisplayConfig::none().with_years(),
+				&Text::default(),
+			),
+			Ok(String::from("524285 years"))
+		)
+	}
+	#[test]
+	fn huge_calendar_interval() {
+		assert_eq!(
+			with_date(
+				-Duration::days(365 * 200_000),
+				date_year_month_day(2000, 1, 1),
+				&DisplayConfig::default(),
+				&Text::default(),
+			),
+			Ok(String::from("199867 years, 2 months and 17 days"))
+		)
+	}
+	#[test]
+	fn too_many_decimals() {
+		assert_eq!(
+			without_date(
+				Duration::seconds(1),
+				&DisplayConfigConstant {
+					seconds: Some(DisplaySettings::new(0.., 0, false).with_decimals(20)),
+					..DisplayConfigConstant::none()
+				},
+				&Text::default(),
+			),
+			Err(StringifyError::NumberOutOfRange {
+				unit: Some(Unit::Seconds),
+				interval: Duration::seconds(1),
+				date: None,
+			})
+		)
+	}
+	#[test]
+	fn empty_label() {
+		assert_eq!(
+			without_date(
+				Duration::seconds(1),
+				&DisplayConfigConstant::default(),
+				&Text {
+					minutes: ThresholdMap::from_iter("{n} minutes", [(1, "")]).unwrap(),
+					..Default::default()
+				},
+			),
+			Err(StringifyError::EmptyLabel {
+				unit: Unit::Minutes
+			})
+		)
+	}
+	#[test]
+	fn label_without_count() {
+		assert_eq!(
+			without_date(
+				Duration::days(5),
+				&DisplayConfigConstant::default(),
+				&Text {
+					days: ThresholdMap::from_iter("days", [(1, "day"), (2, "days")]).unwrap(),
+					..Default::default()
+				},
+			),
+			Ok(String::from("5 days"))
+		);
+	}
+	#[test]
+	fn repeated_count() {
+		let error = without_date(
+			Duration::hours(5),
+			&DisplayConfigConstant::default(),
+			&Text {
+				hours: ThresholdMap::single_value("{n}h{n}"),
+				..Default::default()
+			},
+		)
+		.unwrap_err();
+		assert_eq!(error, StringifyError::RepeatedCount { unit: Unit::Hours });
+		assert_eq!(error.to_string(), "The text for hours has {n} more than once");
+	}
+	#[test]
+	fn inverted_range() {
+		let (lower, upper) = (5, 2);
+		let error = with_date(
+			Duration::seconds(1),
+			date_year_month_day(2000, 1, 1),
+			&DisplayConfig {
+				months: Some(DisplaySettings::new(lower..upper, 0, false)),
+				..DisplayConfig::default()
+			},
+			&Text::default(),
+		)
+		.unwrap_err();
+		assert_eq!(error.kind(), ErrorKind::InvertedRange);
+		assert_eq!(
+			error.to_string(),
+			"The display range for months starts at 5, but ends before 2, so it is empty"
+		);
+		// The end is exclusive, so a range that ends right before it starts is empty too.
+		let error = with_date(
+			Duration::seconds(1),
+			date_year_month_day(2000, 1, 1),
+			&DisplayConfig {
+				months: Some(DisplaySettings::new(lower..=lower - 1, 0, false)),
+				..DisplayConfig::default()
+			},
+			&Text::default(),
+		)
+		.unwrap_err();
+		assert_eq!(error.kind(), ErrorKind::InvertedRange);
+	}
+	/// Every combination of extreme intervals and dates with the configuration should give a result or `NumberOutOfRange`, never panic.
+	fn assert_boundaries(config: &DisplayConfig) {
+		let intervals = [
+			Duration::MIN,
+			Duration::MIN + Duration::milliseconds(1),
+			Duration::MAX,
+			Duration::MAX - Duration::seconds(1),
+			Duration::days(365 * 300_000),
+			-Duration::days(365 * 300_000),
+			DateTime::<Utc>::MAX_UTC - DateTime::<Utc>::MIN_UTC,
+			Duration::milliseconds(-1),
+			Duration::zero(),
+		];
+		let dates = [
+			DateTime::<Utc>::MIN_UTC,
+			DateTime::<Utc>::MAX_UTC,
+			date_year_month_day(2000, 1, 31),
+			date_year_month_day(-262_000, 2, 29),
+			date_year_month_day(262_000, 12, 31),
+		];
+		for interval in intervals {
+			for date in dates {
+				match with_date(interval, date, config, &Text::default()) {
+					Ok(_) => (),
+					Err(error) if error.kind() == ErrorKind::NumberOutOfRange => (),
+					Err(error) => panic!("{error:?} for {interval} from {date}"),
+				}
+			}
+		}
+	}
+	#[test]
+	fn boundaries_default() {
+		assert_boundaries(&DisplayConfig::default());
+	}
+	#[test]
+	fn boundaries_years_and_months() {
+		assert_boundaries(&DisplayConfig::none().with_years());
+		assert_boundaries(&DisplayConfig::none().with_months());
+		assert_boundaries(&DisplayConfig::none().with_years().with_months());
+		assert_boundaries(&DisplayConfig::none().with_years().with_seconds());
+	}
+	#[test]
+	fn boundaries_weeks() {
+		assert_boundaries(&DisplayConfig::none().with_weeks());
+		assert_boundaries(&DisplayConfig {
+			weeks: Some(DisplaySettings::new(0.., 255, true).with_decimals(19)),
+			..DisplayConfig::none()
+		});
+	}
+	#[test]
+	fn boundaries_every_unit_padded() {
+		let settings = DisplaySettings::new(0.., 255, true);
+		assert_boundaries(&DisplayConfig {
+			years: Some(settings),
+			months: Some(settings),
+			weeks: Some(settings),
+			days: Some(settings),
+			hours: Some(settings),
+			minutes: Some(settings),
+			seconds: Some(settings.with_decimals(19)),
+			..DisplayConfig::none()
+		});
+	}
+
+	/// Central European time, with daylight saving time from the last Sunday of March to the last Sunday of October, changing at 01:00 UTC.
+	#[derive(Debug, Clone, Copy)]
+	struct CentralEurope;
+
+	impl CentralEurope {
+		const WINTER: i32 = 60 * 60;
+		const SUMMER: i32 = 2 * 60 * 60;
+
+		fn offset(seconds: i32) -> FixedOffset {
+			FixedOffset::east_opt(seconds).unwrap()
+		}
+		fn last_sunday_change(year: i32, month: u32) -> Option<NaiveDateTime> {
+			let last = NaiveDate::from_ymd_opt(year, month, 31)?;
+			let sunday = last
+				.checked_sub_signed(Duration::days(last.weekday().num_days_from_sunday().into()))?;
+			sunday.and_hms_opt(1, 0, 0)
+		}
+		fn is_summer(utc: &NaiveDateTime) -> bool {
+			let year = utc.year();
+			Self::last_sunday_change(year, 3).is_some_and(|start| *utc >= start)
+				&& Self::last_sunday_change(year, 10).is_some_and(|end| *utc < end)
+		}
+	}
+
+	impl TimeZone for CentralEurope {
+		type Offset = FixedOffset;
+
+		fn from_offset(_: &FixedOffset) -> Self {
+			Self
+		}
+		fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+			self.offset_from_local_datetime(&local.and_time(NaiveTime::default()))
+		}
+		fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+			// The summer offset first, since it is the earlier of the two moments.
+			let valid: Vec<FixedOffset> = [Self::SUMMER, Self::WINTER]
+				.into_iter()
+				.map(Self::offset)
+				.filter(|offset| {
+					local
+						.checked_sub_signed(Duration::seconds(offset.local_minus_utc().into()))
+						.is_some_and(|utc| self.offset_from_utc_datetime(&utc) == *offset)
+				})
+				.collect();
+			match valid[..] {
+				[offset] => LocalResult::Single(offset),
+				[earlier, later] => LocalResult::Ambiguous(earlier, later),
+				_ => LocalResult::None,
+			}
+		}
+		fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+			self.offset_from_utc_datetime(&utc.and_time(NaiveTime::default()))
+		}
+		fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+			Self::offset(if Self::is_summer(utc) {
+				Self::SUMMER
+			} else {
+				Self::WINTER
+			})
+		}
+	}
+
+	fn central_europe(year: i32, month: u32, day: u32, hour: u32) -> DateTime<CentralEurope> {
+		CentralEurope
+			.with_ymd_and_hms(year, month, day, hour, 0, 0)
+			.earliest()
+			.unwrap()
+	}
+
+	/// Property tests over arbitrary inputs, which proptest shrinks to a small case when one fails.
+	#[cfg(not(target_arch = "wasm32"))]
+	mod properties {
+		use proptest::prelude::{
+			any, prop, prop_assert, prop_oneof, proptest, Just, ProptestConfig, Strategy,
+		};
+
+		use super::*;
+
+		/// Mostly small numbers, sometimes anything.
+		fn any_number() -> impl Strategy<Value = u64> {
+			prop_oneof![any::<u64>(), 0..1_000_000_u64, 0..100_u64]
+		}
+		fn any_settings() -> impl Strategy<Value = Option<DisplaySettings>> {
+			let range = prop_oneof![
+				any_number().prop_map(|lower| DisplayRange::from(lower..)),
+				(any_number(), any_number()).prop_map(|(lower, length)| DisplayRange::from(
+					lower..lower.saturating_add(length)
+				)),
+				(0..1_000_000_000_i64)
+					.prop_map(|start| DisplayRange::interval(Duration::seconds(start)..)),
+			];
+			let settings = (range, 0..8_u8, any::<bool>(), 0..22_u8).prop_map(
+				|(range, pad, display_zero, decimals)| DisplaySettings {
+					range,
+					pad,
+					display_zero,
+					decimals,
+				},
+			);
+			prop::option::weighted(2.0 / 3.0, settings)
+		}
+		fn any_interval() -> impl Strategy<Value = Duration> {
+			prop_oneof![
+				any::<i64>().prop_map(|milliseconds| Duration::milliseconds(milliseconds / 2)),
+				(0..1_000_i64)
+					.prop_map(|milliseconds| Duration::MIN + Duration::milliseconds(milliseconds)),
+				(0..1_000_i64)
+					.prop_map(|milliseconds| Duration::MAX - Duration::milliseconds(milliseconds)),
+				(-500_000_000..500_000_000_i64).prop_map(Duration::seconds),
+			]
+		}
+		fn any_date() -> impl Strategy<Value = DateTime<Utc>> {
+			(DateTime::<Utc>::MIN_UTC.timestamp()..=DateTime::<Utc>::MAX_UTC.timestamp())
+				.prop_map(|timestamp| DateTime::from_timestamp(timestamp, 0).unwrap())
+		}
+		/// The built-in constant units with ticks of any length in between, or none.
+		fn any_constant_units() -> impl Strategy<Value = Option<Vec<ConstantUnit>>> {
+			let tick = (any_number(), any_settings(), any::<prop::sample::Index>());
+			let units = (
+				prop::collection::vec(any_settings(), 5),
+				prop::collection::vec(tick, 0..3),
+			);
+			prop::option::of(units).prop_map(|units| {
+				let (settings, ticks) = units?;
+				let mut units = DisplayConfig::none().built_in_units(&Text::default());
+				for (unit, settings) in units.iter_mut().zip(settings) {
+					unit.settings = settings;
+				}
+				for (seconds, settings, index) in ticks {
+					let mut tick = ConstantUnit::new(
+						"ticks",
+						Duration::seconds(seconds.clamp(1, i64::MAX as u64 / 1_000) as i64),
+						DisplaySettings::new(.., 0, false),
+						ThresholdMap::single_value("{n} ticks"),
+					);
+					tick.settings = settings;
+					units.insert(index.index(units.len() + 1), tick);
+				}
+				Some(units)
+			})
+		}
+		/// A preset with any labels for one of its units, and any joiners and number format.
+		fn any_text() -> impl Strategy<Value = Text> {
+			let labels = (
+				0..10_usize,
+				"[a-z ]{0,4}",
+				prop::collection::btree_map(1..30_u64, "[a-z ]{0,4}", 0..4),
+				prop::option::of("[a-z ]{0,4}"),
+			);
+			let number_format = (
+				prop::option::of("[,.' ]"),
+				0..5_u8,
+				prop::sample::select(vec![".", ",", "٫"]),
+				prop::sample::select(vec![
+					NumberFormat::ASCII_DIGITS,
+					NumberFormat::ARABIC_INDIC_DIGITS,
+				]),
+			);
+			(
+				prop_oneof![
+					Just(Text::default()),
+					Just(Text::compact()),
+					Just(Text::german())
+				],
+				prop::option::of(labels),
+				"[,;]? ?",
+				prop::option::of(" [a-z&]{0,3} "),
+				number_format,
+			)
+				.prop_map(|(mut text, labels, joiner, final_joiner, number_format)| {
+					if let Some((unit, lowest, thresholds, fraction)) = labels {
+						let template = |label: &str| alloc::format!("{{n}}{label}");
+						let mut map = ThresholdMap::from_iter(
+							template(&lowest),
+							thresholds
+								.iter()
+								.map(|(&threshold, label)| (threshold, template(label))),
+						)
+						.unwrap();
+						if let Some(fraction) = fraction {
+							map = map.with_fractional_value(template(&fraction));
+						}
+						*[
+							&mut text.centuries,
+							&mut text.decades,
+							&mut text.years,
+							&mut text.quarters,
+							&mut text.months,
+							&mut text.weeks,
+							&mut text.days,
+							&mut text.hours,
+							&mut text.minutes,
+							&mut text.seconds,
+						][unit] = map;
+					}
+					text.joiner = joiner;
+					text.final_joiner = final_joiner;
+					let (grouping_separator, grouping_size, decimal_separator, digits) =
+						number_format;
+					text.number_format = NumberFormat {
+						grouping_separator,
+						grouping_size,
+						decimal_separator: decimal_separator.into(),
+						digits,
+					};
+					text
+				})
+		}
+
+		proptest! {
+			#![proptest_config(ProptestConfig::with_cases(2_000))]
+
+			/// Any interval, date, configuration and text is either written or rejected with an error, never with a panic or an internal error.
+			#[test]
+			fn any_configuration(
+				interval in any_interval(),
+				date in any_date(),
+				in_central_europe in any::<bool>(),
+				settings in prop::collection::vec(any_settings(), 10),
+				constant_units in any_constant_units(),
+				phrasing in prop::sample::select(vec![Phrasing::Standalone, Phrasing::Relative, Phrasing::Duration]),
+				month_arithmetic in prop::sample::select(vec![MonthArithmetic::Clamp, MonthArithmetic::Overflow, MonthArithmetic::DayReached]),
+				anchor in prop::sample::select(vec![Anchor::ReferenceDate, Anchor::Start, Anchor::End]),
+				calendar_days in any::<bool>(),
+				text in any_text(),
+			) {
+				let mut config = DisplayConfig {
+					centuries: settings[0],
+					decades: settings[1],
+					years: settings[2],
+					quarters: settings[3],
+					months: settings[4],
+					weeks: settings[5],
+					days: settings[6],
+					hours: settings[7],
+					minutes: settings[8],
+					seconds: settings[9],
+					constant_units: None,
+					phrasing,
+					month_arithmetic,
+					anchor,
+					calendar_days,
+				};
+				if let Some(units) = constant_units {
+					config = config.with_constant_units(units);
+				}
+				let constant = DisplayConfigConstant {
+					weeks: config.weeks,
+					days: config.days,
+					hours: config.hours,
+					minutes: config.minutes,
+					seconds: config.seconds,
+					constant_units: config.constant_units.clone(),
+					phrasing,
+				};
+				let with_date = if in_central_europe {
+					with_date(interval, date.with_timezone(&CentralEurope), &config, &text)
+				} else {
+					with_date(interval, date, &config, &text)
+				};
+				for output in [with_date, without_date(interval, &constant, &text)] {
+					let kind = output.map_err(|error| error.kind()).err();
+					prop_assert!(
+						!matches!(
+							kind,
+							Some(ErrorKind::InternalRounding | ErrorKind::InternalMissingSettings)
+						),
+						"{:?}",
+						kind
+					);
+				}
+			}
+		}
+	}
+
+	fn config_seconds_below_ten_minutes(range: impl Into<DisplayRange>) -> DisplayConfigConstant {
+		DisplayConfigConstant {
+			minutes: Some(DisplaySettings::new(.., 0, false)),
+			seconds: Some(DisplaySettings::new(range, 0, true)),
+			..DisplayConfigConstant::none()
+		}
+	}
+	#[test]
+	fn range_excludes_end() {
+		assert_eq!(
+			without_date(
+				Duration::minutes(10),
+				&config_seconds_below_ten_minutes(..600),
+				&Text::default(),
+			),
+			Ok(String::from("10 minutes"))
+		)
+	}
+	#[test]
+	fn range_inclusive_includes_end() {
+		assert_eq!(
+			without_date(
+				Duration::minutes(10),
+				&config_seconds_below_ten_minutes(0..=600),
+				&Text::default(),
+			),
+			Ok(String::from("10 minutes and 0 seconds"))
+		)
+	}
+	#[test]
+	fn range_inclusive_to_max() {
+		assert_eq!(
+			without_date(
+				Duration::MAX,
+				&config_seconds_below_ten_minutes(..=u64::MAX),
+				&Text::default(),
+			),
+			Ok(String::from("153722867280912 minutes and 55 seconds"))
+		)
+	}
+	#[test]
+	fn interval_range() {
+		let config =
+			config_seconds_below_ten_minutes(DisplayRange::interval(..Duration::minutes(10)));
+		let text = |interval| without_date(interval, &config, &Text::default());
+		assert_eq!(
+			text(Duration::seconds(599)),
+			Ok(String::from("9 minutes and 59 seconds"))
+		);
+		assert_eq!(text(Duration::seconds(600)), Ok(String::from("10 minutes")));
+		assert_eq!(text(Duration::seconds(630)), Ok(String::from("11 minutes")));
+	}
+	#[test]
+	fn interval_range_calendar() {
+		let config = DisplayConfig {
+			years: Some(DisplaySettings::new(
+				DisplayRange::interval(Duration::days(730)..),
+				0,
+				false,
+			)),
+			months: Some(DisplaySettings::new(.., 0, false)),
+			..DisplayConfig::none()
+		};
+		let text = |interval| {
+			with_date(
+				interval,
+				date_year_month_day(2000, 1, 1),
+				&config,
+				&Text::default(),
+			)
+		};
+		assert_eq!(text(Duration::days(700)), Ok(String::from("23 months")));
+		assert_eq!(
+			text(Duration::days(760)),
+			Ok(String::from("2 years and 1 month"))
+		);
+	}
+
+	fn config_months_weeks_days() -> DisplayConfig {
+		DisplayConfig {
+			years: None,
+			weeks: Some(DisplaySettings::new(.., 0, false)),
+			hours: None,
+			minutes: None,
+			seconds: None,
+			..DisplayConfig::default()
+		}
+	}
+	fn months_weeks(days: i64, config: &DisplayConfig) -> Result<String, StringifyError> {
+		with_date(
+			Duration::days(days) + Duration::hours(5),
+			date_year_month_day(2001, 1, 1),
+			config,
+			&Text::default(),
+		)
+	}
+	#[test]
+	fn months_weeks_days() {
+		assert_eq!(
+			months_weeks(31 + 17, &config_months_weeks_days()),
+			Ok(String::from("1 month, 2 weeks and 3 days"))
+		)
+	}
+	#[test]
+	fn months_rounded_to_weeks() {
+		let config = DisplayConfig {
+			days: None,
+			..config_months_weeks_days()
+		};
+		// 10 days are closer to 1 week than to 2, however the whole interval rounds.
+		assert_eq!(
+			months_weeks(31 + 10, &config),
+			Ok(String::from("1 month and 1 week"))
+		);
+		assert_eq!(
+			months_weeks(31 + 11, &config),
+			Ok(String::from("1 month and 2 weeks"))
+		);
+		// February 2001 has exactly 4 weeks, so 25 days into it are closer to March than to 4 weeks.
+		assert_eq!(months_weeks(31 + 25, &config), Ok(String::from("2 months")));
+		// 30 days into March are closer to April than to 4 weeks.
+		assert_eq!(months_weeks(59 + 30, &config), Ok(String::from("3 months")));
+		assert_eq!(
+			months_weeks(59 + 27, &config),
+			Ok(String::from("2 months and 4 weeks"))
+		);
+	}
+	#[test]
+	fn years_rounded_to_weeks() {
+		let config = DisplayConfig {
+			years: Some(DisplaySettings::new(.., 0, false)),
+			months: None,
+			days: None,
+			..config_months_weeks_days()
+		};
+		assert_eq!(
+			months_weeks(365 + 30, &config),
+			Ok(String::from("1 year and 4 weeks"))
+		);
+		// A year is 52 weeks and a day, so it takes more than half a day past 52 weeks to round up to it.
+		assert_eq!(
+			months_weeks(365 + 364, &config),
+			Ok(String::from("1 year and 52 weeks"))
+		);
+		assert_eq!(
+			with_date(
+				Duration::days(365 + 364) + Duration::hours(13),
+				date_year_month_day(2001, 1, 1),
+				&config,
+				&Text::default(),
+			),
+			Ok(String::from("2 years"))
+		);
+		let config = DisplayConfig {
+			years: Some(DisplaySettings::new(.., 0, false)),
+			days: None,
+			..config_months_weeks_days()
+		};
+		// Carrying into December carries into the years.
+		assert_eq!(
+			months_weeks(365 + 364, &config),
+			Ok(String::from("2 years"))
+		);
+	}
+	#[test]
+	fn calendar_remainder_rounded() {
+		let config = DisplayConfig::none().with_months().with_hours();
+		let from_january = |interval| {
+			with_date(
+				interval,
+				date_year_month_day(2001, 1, 1),
+				&config,
+				&Text::default(),
+			)
+		};
+		// What is left after the month is rounded to the nearest hour, like an interval without months would be.
+		assert_eq!(
+			from_january(Duration::days(31) + Duration::minutes(5 * 60 + 40)),
+			Ok(String::from("1 month and 6 hours"))
+		);
+		assert_eq!(
+			from_january(Duration::days(31) + Duration::minutes(5 * 60 + 20)),
+			Ok(String::from("1 month and 5 hours"))
+		);
+	}
+
+	#[test]
+	fn quarters() {
+		assert_eq!(
+			with_date(
+				Duration::days(31 + 28 + 31 + 30 + 31 + 30 + 31 + 3),
+				date_year_month_day(2001, 1, 1),
+				&DisplayConfig::none()
+					.with_quarters()
+					.with_months()
+					.with_days(),
+				&Text::default(),
+			),
+			Ok(String::from("2 quarters, 1 month and 3 days"))
+		)
+	}
+	#[test]
+	fn centuries_decades_years() {
+		assert_eq!(
+			with_date(
+				date_year_month_day(2024, 3, 1) - date_year_month_day(1891, 3, 1),
+				date_year_month_day(1891, 3, 1),
+				&DisplayConfig::none()
+					.with_centuries()
+					.with_decades()
+					.with_years(),
+				&Text::default(),
+			),
+			Ok(String::from("1 century, 3 decades and 3 years"))
+		)
+	}
+	#[test]
+	fn rounded_to_decades() {
+		let config = DisplayConfig::none().with_decades();
+		let text = |end_year| {
+			with_date(
+				date_year_month_day(end_year, 1, 1) - date_year_month_day(2000, 1, 1),
+				date_year_month_day(2000, 1, 1),
+				&config,
+				&Text::default(),
+			)
+		};
+		assert_eq!(text(2034), Ok(String::from("3 decades")));
+		assert_eq!(text(2036), Ok(String::from("4 decades")));
+	}
+	#[test]
+	fn quarters_range() {
+		let config = DisplayConfig {
+			quarters: Some(DisplaySettings::new(..3, 0, false)),
+			..DisplayConfig::none().with_years().with_months()
+		};
+		let text = |months: u32| {
+			with_date(
+				date_year_month_day(2001, 1 + months, 1) - date_year_month_day(2001, 1, 1),
+				date_year_month_day(2001, 1, 1),
+				&config,
+				&Text::default(),
+			)
+		};
+		assert_eq!(text(7), Ok(String::from("2 quarters and 1 month")));
+		assert_eq!(text(11), Ok(String::from("11 months")));
+	}
+	#[test]
+	fn quarters_in_the_past() {
+		assert_eq!(
+			with_date(
+				-(date_year_month_day(2001, 1, 1) - date_year_month_day(2000, 6, 1)),
+				date_year_month_day(2001, 1, 1),
+				&DisplayConfig::none().with_quarters().with_months(),
+				&Text::default(),
+			),
+			Ok(String::from("2 quarters and 1 month"))
+		)
+	}
+
+	fn constant_unit(length: Duration, singular: &str, plural: &str) -> ConstantUnit {
+		ConstantUnit::new(
+			plural,
+			length,
+			DisplaySettings::new(.., 0, false),
+			ThresholdMap::from_iter(
+				format!("{{n}} {plural}"),
+				[
+					(1, format!("{{n}} {singular}")),
+					(2, format!("{{n}} {plural}")),
+				],
+			)
+			.unwrap(),
+		)
+	}
+	#[test]
+	fn constant_units_in_list_order() {
+		let text = Text::default();
+		let hours = DisplayConfigConstant::none()
+			.with_hours()
+			.built_in_units(&text)
+			.remove(2);
+		let shift = constant_unit(Duration::hours(8), "shift", "shifts");
+		let sprint = constant_unit(Duration::days(14), "sprint", "sprints");
+		let interval = Duration::days(15) + Duration::hours(17);
+		let config = DisplayConfigConstant::none().with_constant_units(vec![
+			sprint.clone(),
+			shift.clone(),
+			hours.clone(),
+		]);
+		assert_eq!(
+			without_date(interval, &config, &text),
+			Ok(String::from("1 sprint, 5 shifts and 1 hour"))
+		);
+		// Shifts before sprints leave nothing for them.
+		let config = DisplayConfigConstant::none().with_constant_units(vec![shift, sprint, hours]);
+		assert_eq!(
+			without_date(interval, &config, &text),
+			Ok(String::from("47 shifts and 1 hour"))
+		);
+	}
+	#[test]
+	fn built_in_units_as_list() {
+		let text = Text::default();
+		let date = central_europe(2021, 3, 28, 0);
+		let two_days = central_europe(2021, 3, 30, 0) - date;
+		for config in [
+			DisplayConfig::default(),
+			DisplayConfig::none()
+				.with_weeks()
+				.with_days()
+				.with_hours()
+				.with_calendar_days(),
+		] {
+			let listed = config
+				.clone()
+				.with_constant_units(config.built_in_units(&text));
+			for interval in [two_days, Duration::seconds(1_234_567), Duration::days(-400)] {
+				assert_eq!(
+					with_date(interval, date, &listed, &text),
+					with_date(interval, date, &config, &text),
+				);
+			}
+		}
+	}
+	#[test]
+	fn constant_unit_rounding() {
+		// 8 hours aren't a whole number of 7 second ticks, so what is left after the shift is rounded to ticks.
+		let config = DisplayConfigConstant::none().with_constant_units(vec![
+			constant_unit(Duration::hours(8), "shift", "shifts"),
+			constant_unit(Duration::seconds(7), "tick", "ticks"),
+		]);
+		let text = |interval| without_date(interval, &config, &Text::default());
+		assert_eq!(
+			text(Duration::hours(8) + Duration::seconds(23)),
+			Ok(String::from("1 shift and 3 ticks"))
+		);
+		// Rounding to ticks would be 4114 ticks, 2 seconds short of a shift, but another shift is 1 second away.
+		assert_eq!(
+			text(Duration::hours(16) - Duration::seconds(1)),
+			Ok(String::from("2 shifts"))
+		);
+	}
+	#[test]
+	fn constant_unit_decimals() {
+		let mut shifts = constant_unit(Duration::hours(8), "shift", "shifts");
+		shifts.settings = Some(DisplaySettings::new(.., 0, false).with_decimals(2));
+		let config = DisplayConfigConstant::none().with_constant_units(vec![shifts]);
+		assert_eq!(
+			without_date(Duration::hours(20), &config, &Text::default()),
+			Ok(String::from("2.5 shifts"))
+		)
+	}
+	#[test]
+	fn constant_unit_context_labels() {
+		let text = Text::german();
+		let mut units = DisplayConfigConstant::none()
+			.with_days()
+			.built_in_units(&text);
+		let mut workdays = constant_unit(Duration::hours(8), "Tagewerk", "Tagewerke");
+		workdays.labels.past = Some(
+			ThresholdMap::from_iter(
+				"{n} Tagewerken",
+				[(1, "{n} Tagewerk"), (2, "{n} Tagewerken")],
+			)
+			.unwrap(),
+		);
+		units.insert(2, workdays);
+		let config = DisplayConfigConstant::none()
+			.with_constant_units(units)
+			.with_phrasing(Phrasing::Relative);
+		let interval = Duration::days(3) + Duration::hours(16);
+		assert_eq!(
+			without_date(-interval, &config, &text),
+			Ok(String::from("vor 3 Tagen und 2 Tagewerken"))
+		);
+		assert_eq!(
+			without_date(interval, &config, &text),
+			Ok(String::from("in 3 Tagen und 2 Tagewerke"))
+		);
+	}
+	#[test]
+	fn constant_units_replace_built_in_settings() {
+		let shifts = constant_unit(Duration::hours(8), "shift", "shifts");
+		let config = DisplayConfigConstant {
+			constant_units: Some(vec![shifts.clone()]),
+			..DisplayConfigConstant::default()
+		};
+		let error = without_date(Duration::hours(8), &config, &Text::default()).unwrap_err();
+		assert_eq!(error, StringifyError::ReplacedUnit { unit: Unit::Days });
+		assert_eq!(
+			error.to_string(),
+			"The days have display settings, but the list of constant units replaces them"
+		);
+		// The builder leaves out the settings it replaces.
+		let config = DisplayConfigConstant::default().with_constant_units(vec![shifts]);
+		assert_eq!(
+			without_date(Duration::hours(8), &config, &Text::default()),
+			Ok(String::from("1 shift"))
+		);
+	}
+	#[test]
+	fn constant_unit_too_short() {
+		let config = DisplayConfigConstant::default().with_constant_units(vec![constant_unit(
+			Duration::milliseconds(500),
+			"blink",
+			"blinks",
+		)]);
+		let error = without_date(Duration::seconds(1), &config, &Text::default()).unwrap_err();
+		assert_eq!(
+			error,
+			StringifyError::InvalidLength {
+				unit: Unit::Custom(String::from("blinks")),
+				length: Duration::milliseconds(500),
+			}
+		);
+		assert_eq!(
+			error.to_string(),
+			"The length of blinks is PT0.5S, but it must be at least one second"
+		);
+	}
+	#[test]
+	fn constant_unit_empty_label() {
+		let mut shifts = constant_unit(Duration::hours(8), "shift", "shifts");
+		shifts.labels = ThresholdMap::single_value(String::new()).into();
+		let config = DisplayConfigConstant::default().with_constant_units(vec![shifts]);
+		assert_eq!(
+			without_date(Duration::seconds(1), &config, &Text::default()),
+			Err(StringifyError::EmptyLabel {
+				unit: Unit::Custom(String::from("shifts"))
+			})
+		)
+	}
+
+	/// For each pair of dates, the output with `Clamp`, `Overflow` and `DayReached`.
+	#[test]
+	fn month_arithmetic() {
+		let table = [
+			(
+				(2001, 1, 31),
+				(2001, 2, 28),
+				["1 month", "28 days", "28 days"],
+			),
+			(
+				(2001, 1, 31),
+				(2001, 3, 1),
+				["1 month and 1 day", "29 days", "1 month"],
+			),
+			(
+				(2001, 1, 31),
+				(2001, 3, 3),
+				["1 month and 3 days", "1 month", "1 month and 2 days"],
+			),
+			(
+				(2001, 1, 31),
+				(2001, 3, 31),
+				["2 months", "2 months", "2 months"],
+			),
+			(
+				(2004, 1, 31),
+				(2004, 2, 29),
+				["1 month", "29 days", "29 days"],
+			),
+			(
+				(2004, 1, 31),
+				(2004, 3, 2),
+				["1 month and 2 days", "1 month", "1 month and 1 day"],
+			),
+			((2001, 1, 15), (2001, 3, 14), ["1 month and 27 days"; 3]),
+			(
+				(2000, 2, 29),
+				(2001, 2, 28),
+				["1 year", "11 months and 30 days", "11 months and 30 days"],
+			),
+			(
+				(2000, 2, 29),
+				(2001, 3, 1),
+				["1 year and 1 day", "1 year", "1 year"],
+			),
+		];
+		let policies = [
+			MonthArithmetic::Clamp,
+			MonthArithmetic::Overflow,
+			MonthArithmetic::DayReached,
+		];
+		for (from, to, expected) in table {
+			for (expected, policy) in expected.into_iter().zip(policies) {
+				let config = DisplayConfig::none()
+					.with_years()
+					.with_months()
+					.with_days()
+					.with_month_arithmetic(policy);
+				let from = date_year_month_day(from.0, from.1, from.2);
+				let to = date_year_month_day(to.0, to.1, to.2);
+				assert_eq!(
+					with_date(to - from, from, &config, &Text::default()),
+					Ok(String::from(expected)),
+					"forwards from {from} to {to} with {policy:?}"
+				);
+			}
+		}
+	}
+	/// Going back, a day that doesn't exist is adjusted the same way, in the month that is reached.
+	#[test]
+	fn month_arithmetic_backwards() {
+		let table = [
+			(
+				(2001, 3, 31),
+				(2001, 2, 28),
+				["1 month", "1 month and 3 days", "1 month and 1 day"],
+			),
+			(
+				(2001, 3, 31),
+				(2001, 3, 3),
+				["28 days", "1 month", "28 days"],
+			),
+			(
+				(2001, 3, 31),
+				(2001, 3, 1),
+				["30 days", "1 month and 2 days", "1 month"],
+			),
+		];
+		let policies = [
+			MonthArithmetic::Clamp,
+			MonthArithmetic::Overflow,
+			MonthArithmetic::DayReached,
+		];
+		for (from, to, expected) in table {
+			for (expected, policy) in expected.into_iter().zip(policies) {
+				let config = DisplayConfig::none()
+					.with_months()
+					.with_days()
+					.with_month_arithmetic(policy);
+				let from = date_year_month_day(from.0, from.1, from.2);
+				let to = date_year_month_day(to.0, to.1, to.2);
+				assert_eq!(
+					with_date(to - from, from, &config, &Text::default()),
+					Ok(String::from(expected)),
+					"backwards from {from} to {to} with {policy:?}"
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn anchor() {
+		let start = date_year_month_day(2001, 1, 31);
+		let end = date_year_month_day(2001, 3, 30);
+		let text = |interval, date, anchor| {
+			with_date(
+				interval,
+				date,
+				&DisplayConfig::none()
+					.with_months()
+					.with_days()
+					.with_anchor(anchor),
+				&Text::default(),
+			)
+		};
+		// The same span, given from either end.
+		for (interval, date) in [(end - start, start), (start - end, end)] {
+			assert_eq!(
+				text(interval, date, Anchor::Start),
+				Ok(String::from("1 month and 30 days"))
+			);
+			assert_eq!(
+				text(interval, date, Anchor::End),
+				Ok(String::from("1 month and 28 days"))
+			);
+		}
+		assert_eq!(
+			text(end - start, start, Anchor::ReferenceDate),
+			Ok(String::from("1 month and 30 days"))
+		);
+		assert_eq!(
+			text(start - end, end, Anchor::ReferenceDate),
+			Ok(String::from("1 month and 28 days"))
+		);
+	}
+
+	#[test]
+	fn calendar_days_across_dst() {
+		let config = DisplayConfig::none().with_weeks().with_days().with_hours();
+		let text = |interval, date, config: &DisplayConfig| {
+			with_date(interval, date, config, &Text::default())
+		};
+		// Clocks go forward on March 28th 2021, so those two days are 47 hours long.
+		let spring = central_europe(2021, 3, 28, 0);
+		let two_days = central_europe(2021, 3, 30, 0) - spring;
+		assert_eq!(two_days, Duration::hours(47));
+		assert_eq!(
+			text(two_days, spring, &config),
+			Ok(String::from("1 day and 23 hours"))
+		);
+		assert_eq!(
+			text(two_days, spring, &config.clone().with_calendar_days()),
+			Ok(String::from("2 days"))
+		);
+		// Clocks go back on October 31st 2021, so that week is 169 hours long.
+		let autumn = central_europe(2021, 11, 1, 12);
+		let week = central_europe(2021, 10, 25, 12) - autumn;
+		assert_eq!(week, Duration::hours(-169));
+		assert_eq!(
+			text(week, autumn, &config),
+			Ok(String::from("1 week and 1 hour"))
+		);
+		assert_eq!(
+			text(week, autumn, &config.clone().with_calendar_days()),
+			Ok(String::from("1 week"))
+		);
+		assert_eq!(
+			text(
+				week + Duration::hours(1),
+				autumn,
+				&config.clone().with_calendar_days()
+			),
+			Ok(String::from("6 days and 23 hours"))
+		);
+	}
+
+	#[test]
+	fn calendar_days_with_months() {
+		let config = DisplayConfig::none()
+			.with_months()
+			.with_weeks()
+			.with_days()
+			.with_calendar_days();
+		// The remainder is rounded to a calendar day, and one more day can carry into a month.
+		let date = central_europe(2021, 3, 1, 12);
+		for (to, expected) in [
+			((2021, 3, 29, 11), "4 weeks"),
+			((2021, 4, 1, 1), "1 month"),
+			((2021, 4, 9, 12), "1 month, 1 week and 1 day"),
+		] {
+			let to = central_europe(to.0, to.1, to.2, to.3);
+			assert_eq!(
+				with_date(to - date, date, &config, &Text::default()),
+				Ok(String::from(expected)),
+				"to {to}"
+			);
+		}
+	}
+
+	#[test]
+	fn months_in_time_zone() {
+		// January 31st in UTC, but still January 30th at the reference date's offset.
+		let date = FixedOffset::west_opt(5 * 60 * 60)
+			.unwrap()
+			.with_ymd_and_hms(2021, 1, 30, 20, 0, 0)
+			.unwrap();
+		let config = DisplayConfig::none().with_months().with_days();
+		assert_eq!(
+			with_date(Duration::days(29), date, &config, &Text::default()),
+			Ok(String::from("1 month"))
+		);
+		assert_eq!(
+			with_date(
+				Duration::days(29),
+				date.with_timezone(&Utc),
+				&config,
+				&Text::default()
+			),
+			Ok(String::from("1 month and 1 day"))
+		);
+	}
+
+	#[test]
+	fn business_time_skips_weekend() {
+		// From Friday 15:00 to Wednesday 11:00: 2 hours on Friday, 8 on Monday and Tuesday, and 2 on Wednesday.
+		let friday = central_europe(2021, 3, 26, 15);
+		let wednesday = central_europe(2021, 3, 31, 11);
+		let config = DisplayConfigConstant::default();
+		let text = Text {
+			days: ThresholdMap::from_iter(
+				"{n} business days",
+				[(1, "{n} business day"), (2, "{n} business days")],
+			)
+			.unwrap(),
+			..Text::default()
+		};
+		assert_eq!(
+			business_time(
+				wednesday - friday,
+				friday,
+				&WorkWeek::default(),
+				&config,
+				&text
+			),
+			Ok(String::from("2 business days and 4 hours"))
+		);
+		assert_eq!(
+			business_time(
+				friday - wednesday,
+				wednesday,
+				&WorkWeek::default().with_holiday(NaiveDate::from_ymd_opt(2021, 3, 29).unwrap()),
+				&config,
+				&text,
+			),
+			Ok(String::from("1 business day and 4 hours"))
+		);
+	}
+
+	#[test]
+	fn business_weeks() {
+		let monday = date_year_month_day(2024, 1, 1) + Duration::hours(9);
+		let config = DisplayConfigConstant::none().with_weeks().with_days();
+		assert_eq!(
+			business_time(
+				Duration::days(16),
+				monday,
+				&WorkWeek::default(),
+				&config,
+				&Text::default(),
+			),
+			Ok(String::from("2 weeks and 2 days"))
+		);
+		let six_day_week = WorkWeek {
+			weekend: vec![chrono::Weekday::Sun],
+			..WorkWeek::default()
+		};
+		assert_eq!(six_day_week.business_week(), Duration::hours(48));
+		assert_eq!(
+			business_time(
+				Duration::days(16),
+				monday,
+				&six_day_week,
+				&config,
+				&Text::default(),
+			),
+			Ok(String::from("2 weeks and 2 days"))
+		);
+	}
+
+	#[test]
+	fn business_day_too_short() {
+		let hours =
+			NaiveTime::from_hms_opt(17, 0, 0).unwrap()..NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+		assert_eq!(
+			business_time(
+				Duration::days(1),
+				date_year_month_day(2024, 1, 1),
+				&WorkWeek::new(Vec::new(), Vec::new(), hours),
+				&DisplayConfigConstant::default(),
+				&Text::default(),
+			)
+			.map_err(|error| error.kind()),
+			Err(ErrorKind::InvalidLength)
+		);
+	}
+
+	#[test]
+	fn business_hours_without_working_days() {
+		let weekend = core::iter::successors(Some(chrono::Weekday::Mon), |day| Some(day.succ()))
+			.take(7)
+			.collect();
+		let calendar = WorkWeek {
+			weekend,
+			..WorkWeek::default()
+		};
+		let business = |config: &DisplayConfigConstant| {
+			business_time(
+				Duration::days(10),
+				date_year_month_day(2024, 1, 1),
+				&calendar,
+				config,
+				&Text::default(),
+			)
+		};
+		assert_eq!(
+			business(&DisplayConfigConstant::none().with_hours()),
+			Ok(String::from("0 hours"))
+		);
+		assert_eq!(
+			business(&DisplayConfigConstant::none().with_weeks().with_hours())
+				.map_err(|error| error.kind()),
+			Err(ErrorKind::InvalidLength)
+		);
+	}
+
+	#[test]
+	fn business_time_too_long() {
+		let business = |years| {
+			business_time(
+				Duration::days(36_525) * years,
+				date_year_month_day(2024, 1, 1),
+				&WorkWeek::default(),
+				&DisplayConfigConstant::default(),
+				&Text::default(),
+			)
+			.map_err(|error| error.kind())
+		};
+		assert!(business(1).is_ok());
+		assert!(business(-1).is_ok());
+		assert_eq!(business(2), Err(ErrorKind::NumberOutOfRange));
+		assert_eq!(business(-1_000_000), Err(ErrorKind::NumberOutOfRange));
+	}
+
+	#[test]
+	fn relative_words() {
+		// A Wednesday evening.
+		let date = date_year_month_day(2024, 1, 31) + Duration::hours(20);
+		let config = DisplayConfig::default().with_phrasing(Phrasing::Relative);
+		let text = |interval, words: &RelativeText| {
+			relative_date(interval, date, &config, &Text::default(), words)
+		};
+		for (interval, expected) in [
+			(Duration::hours(5), "tomorrow"),
+			(Duration::hours(-21), "yesterday"),
+			(Duration::hours(-3), "3 hours ago"),
+			(Duration::days(-2), "2 days ago"),
+			(Duration::days(-3), "last week"),
+			(Duration::days(5), "next week"),
+			(Duration::days(-12), "12 days ago"),
+			(Duration::days(13), "next month"),
+			(Duration::days(-31), "last month"),
+			(Duration::days(-75), "last year"),
+			(Duration::days(800), "in 2 years, 2 months and 10 days"),
+		] {
+			assert_eq!(
+				text(interval, &RelativeText::default()),
+				Ok(String::from(expected)),
+				"{interval}"
+			);
+		}
+		// Weeks starting on Sunday make the Sunday before part of this week.
+		let sunday_weeks = RelativeText {
+			first_day_of_week: chrono::Weekday::Sun,
+			..RelativeText::default()
+		};
+		assert_eq!(
+			text(Duration::days(-3), &sunday_weeks),
+			Ok(String::from("3 days ago"))
+		);
+		// Without a word for it, the next larger one can still apply.
+		let no_days = RelativeText {
+			tomorrow: None,
+			..RelativeText::default()
+		};
+		assert_eq!(
+			text(Duration::hours(5), &no_days),
+			Ok(String::from("next month"))
+		);
+		assert_eq!(
+			text(Duration::hours(5), &RelativeText::none()),
+			Ok(String::from("in 5 hours"))
+		);
+	}
+
+	#[test]
+	fn relative_words_in_time_zone() {
+		// Still the evening of the 1st at the reference date's offset, but the 2nd in UTC.
+		let date = FixedOffset::west_opt(5 * 60 * 60)
+			.unwrap()
+			.with_ymd_and_hms(2024, 3, 1, 20, 0, 0)
+			.unwrap();
+		let words = RelativeText::default();
+		assert_eq!(
+			relative_date(
+				Duration::hours(-20),
+				date,
+				&DisplayConfig::default(),
+				&Text::default(),
+				&words
+			),
+			Ok(String::from("20 hours"))
+		);
+		assert_eq!(
+			relative_date(
+				Duration::hours(-20),
+				date.with_timezone(&Utc),
+				&DisplayConfig::default(),
+				&Text::default(),
+				&words
+			),
+			Ok(String::from("yesterday"))
+		);
+	}
+
+	#[test]
+	fn countdown_rounding() {
+		// 200.5 minutes round up until there are less than 12_030 seconds left.
+		let interval = Duration::seconds(12_030) + Duration::milliseconds(500);
+		let countdown = countdown_without_date(
+			interval,
+			&DisplayConfigConstant::default(),
+			&Text::default(),
+		)
+		.unwrap();
+		assert_eq!(countdown.text, "3 hours and 21 minutes");
+		assert_eq!(
+			countdown.next_change,
+			Some(Duration::milliseconds(500) + Duration::nanoseconds(1))
+		);
+		// Counting up in the past, 5.5 minutes round to 6.
+		let config = DisplayConfigConstant::none()
+			.with_minutes()
+			.with_phrasing(Phrasing::Relative);
+		let countdown =
+			countdown_without_date(Duration::seconds(-310), &config, &Text::default()).unwrap();
+		assert_eq!(countdown.text, "5 minutes ago");
+		assert_eq!(countdown.next_change, Some(Duration::seconds(20)));
+	}
+
+	#[test]
+	fn countdown_range_comes_back() {
+		// Hours give way to seconds under 5 seconds either side of zero, and then the text is the same as before.
+		let config = DisplayConfigConstant {
+			hours: Some(DisplaySettings::new(
+				DisplayRange::interval(Duration::seconds(5)..),
+				0,
+				false,
+			)),
+			seconds: Some(DisplaySettings::new(
+				DisplayRange::interval(..Duration::seconds(5)),
+				0,
+				false,
+			)),
+			..DisplayConfigConstant::none()
+		};
+		let countdown =
+			countdown_without_date(Duration::seconds(100), &config, &Text::default()).unwrap();
+		assert_eq!(countdown.text, "0 hours");
+		assert_eq!(
+			countdown.next_change,
+			Some(Duration::seconds(95) + Duration::nanoseconds(1))
+		);
+		assert_eq!(
+			without_date(Duration::seconds(-40), &config, &Text::default()),
+			Ok(countdown.text)
+		);
+	}
+
+	#[test]
+	fn countdown_next_change() {
+		let date = date_year_month_day(2021, 1, 1) + Duration::milliseconds(250);
+		let configs = [
+			DisplayConfig::default(),
+			DisplayConfig::none().with_months(),
+			DisplayConfig::none().with_years().with_weeks(),
+			DisplayConfig::none().with_days().with_hours(),
+		];
+		for config in &configs {
+			for interval in [
+				Duration::days(59),
+				Duration::days(400) + Duration::milliseconds(1),
+				Duration::hours(-30) - Duration::milliseconds(700),
+				Duration::seconds(3),
+			] {
+				let countdown =
+					countdown_with_date(interval, date, config, &Text::default()).unwrap();
+				let next_change = countdown.next_change.unwrap();
+				let text_after = |elapsed: Duration| {
+					with_date(interval - elapsed, date + elapsed, config, &Text::default())
+				};
+				assert_eq!(
+					text_after(next_change - Duration::nanoseconds(1)),
+					Ok(countdown.text.clone()),
+					"{interval} with {config:?}"
+				);
+				assert_ne!(
+					text_after(next_change),
+					Ok(countdown.text),
+					"{interval} with {config:?}"
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn write_into_buffer() {
+		/// A buffer of a fixed size, like on a device without an allocator.
+		struct Buffer {
+			bytes: [u8; 16],
+			length: usize,
+		}
+
+		impl fmt::Write for Buffer {
+			fn write_str(&mut self, s: &str) -> fmt::Result {
+				let end = self.length + s.len();
+				self.bytes
+					.get_mut(self.length..end)
+					.ok_or(fmt::Error)?
+					.copy_from_slice(s.as_bytes());
+				self.length = end;
+				Ok(())
+			}
+		}
+
+		let interval = Duration::seconds(93_784);
+		let config = DisplayConfigConstant::default();
+		let mut buffer = Buffer {
+			bytes: [0; 16],
+			length: 0,
+		};
+		assert_eq!(
+			write_without_date(&mut buffer, interval, &config, &Text::compact()),
+			Ok(())
+		);
+		assert_eq!(
+			std::str::from_utf8(&buffer.bytes[..buffer.length]),
+			Ok("1d 2h 3m")
+		);
+		let mut buffer = Buffer {
+			bytes: [0; 16],
+			length: 0,
+		};
+		assert_eq!(
+			write_without_date(&mut buffer, interval, &config, &Text::default()),
+			Err(StringifyError::Write)
+		);
+
+		let mut output = String::from("Uptime: ");
+		write_without_date(
+			&mut output,
+			Duration::seconds(90),
+			&config,
+			&Text::default(),
+		)
+		.unwrap();
+		assert_eq!(output, "Uptime: 1 minute and 30 seconds");
+	}
+
+	/// Parses the arguments with a `--timeout` that takes any duration in the default texts and a `--delay` that takes a `std::time::Duration` in German.
+	#[cfg(feature = "clap")]
+	fn clap_matches(args: &[&str]) -> Result<clap::ArgMatches, String> {
+		use clap::{Arg, Command};
+
+		use crate::clap::DurationParser;
+
+		Command::new("test")
+			.arg(
+				Arg::new("timeout")
+					.long("timeout")
+					.allow_hyphen_values(true)
+					.value_parser(DurationParser::<Duration>::default()),
+			)
+			.arg(
+				Arg::new("delay")
+					.long("delay")
+					.value_parser(DurationParser::<std::time::Duration>::new([Text::german()])),
+			)
+			.try_get_matches_from(["test"].iter().chain(args))
+			.map_err(|error| error.to_string())
+	}
+
+	#[cfg(feature = "clap")]
+	#[test]
+	fn clap_value_parser() {
+		let matches = clap_matches(&["--timeout", "2 hours and 5 minutes"]).unwrap();
+		assert_eq!(
+			matches.get_one::<Duration>("timeout"),
+			Some(&Duration::minutes(125))
+		);
+	}
+
+	#[cfg(feature = "clap")]
+	#[test]
+	fn clap_value_parser_compact() {
+		let matches = clap_matches(&["--timeout", "2h5m"]).unwrap();
+		assert_eq!(
+			matches.get_one::<Duration>("timeout"),
+			Some(&Duration::minutes(125))
+		);
+	}
+
+	#[cfg(feature = "clap")]
+	#[test]
+	fn clap_value_parser_std_duration() {
+		let matches = clap_matches(&["--delay", "1 Tag"]).unwrap();
+		assert_eq!(
+			matches.get_one::<std::time::Duration>("delay"),
+			Some(&std::time::Duration::from_secs(86_400))
+		);
+		assert!(clap_matches(&["--delay", "2 Tage"]).is_ok());
+	}
+
+	#[cfg(feature = "clap")]
+	#[test]
+	fn clap_value_parser_unknown_unit() {
+		let error = clap_matches(&["--timeout", "2 fortnights"]).unwrap_err();
+		assert!(
+			error.starts_with(
+				"error: invalid value '2 fortnights' for '--timeout <timeout>': Unknown unit \"fortnights\", expected one of: weeks, week, days, day, hours, hour, minutes, minute, seconds, second, w, d, h, m, s\n"
+			),
+			"{error}"
+		);
+	}
+
+	#[cfg(feature = "clap")]
+	#[test]
+	fn clap_value_parser_negative_std_duration() {
+		let error = clap_matches(&["--delay", "vor 2 Tagen"]).unwrap_err();
+		assert!(
+			error.contains("'--delay <delay>': The duration must not be negative"),
+			"{error}"
+		);
+	}
+
+	#[cfg(feature = "wasm-bindgen")]
+	fn wasm_text(
+		seconds: f64,
+		date: Option<&str>,
+		config: &str,
+		text: &str,
+	) -> Result<String, String> {
+		use crate::wasm::{from_json, stringify};
+
+		stringify(
+			seconds,
+			date,
+			from_json(config).unwrap(),
+			from_json(text).unwrap(),
+		)
+		.map_err(|error| error.to_string())
+	}
+	#[cfg(feature = "wasm-bindgen")]
+	#[test]
+	fn wasm_defaults() {
+		assert_eq!(
+			wasm_text(1_234_567.0, None, "{}", "{}"),
+			Ok(without_date(
+				Duration::seconds(1_234_567),
+				&DisplayConfigConstant::default(),
+				&Text::default()
+			)
+			.unwrap())
+		);
+	}
+	#[cfg(feature = "wasm-bindgen")]
+	#[test]
+	fn wasm_config_like_rust() {
+		let config = r#"{
+			"units": { "years": {}, "months": {}, "days": { "displayZero": true }, "hours": { "pad": 2 } },
+			"phrasing": "relative",
+			"monthArithmetic": "dayReached"
+		}"#;
+		let rust_config = DisplayConfig {
+			years: Some(DisplaySettings::new(0.., 0, false)),
+			months: Some(DisplaySettings::new(0.., 0, false)),
+			days: Some(DisplaySettings::new(0.., 0, true)),
+			hours: Some(DisplaySettings::new(0.., 2, false)),
+			phrasing: Phrasing::Relative,
+			month_arithmetic: MonthArithmetic::DayReached,
+			..DisplayConfig::none()
+		};
+		let date = date_year_month_day(2001, 1, 31);
+		let text = wasm_text(
+			2_505_600.0,
+			Some("2001-01-31T00:00:00Z"),
+			config,
+			r#"{ "preset": "german" }"#,
+		);
+		assert_eq!(
+			text,
+			Ok(with_date(Duration::days(29), date, &rust_config, &Text::german()).unwrap())
+		);
+		assert_eq!(text, Ok(String::from("in 1 Monat und 0 Tagen")));
+	}
+	#[cfg(feature = "wasm-bindgen")]
+	const WASM_CUSTOM_TEXT: &str = r#"{
+		"preset": "compact",
+		"hours": { "one": "{n} hr", "other": "{n} hrs" },
+		"minutes": "{n} min",
+		"finalJoiner": " & ",
+		"numberFormat": { "decimalSeparator": ",", "digits": "٠١٢٣٤٥٦٧٨٩" }
+	}"#;
+	#[cfg(feature = "wasm-bindgen")]
+	#[test]
+	fn wasm_custom_text() {
+		assert_eq!(
+			wasm_text(3_720.5, None, "{}", WASM_CUSTOM_TEXT),
+			Ok(String::from("١ hr & ٢ min"))
+		);
+	}
+	#[cfg(feature = "wasm-bindgen")]
+	#[test]
+	fn wasm_decimals_and_interval_range() {
+		let config = r#"{ "units": { "hours": { "decimals": 2 }, "seconds": { "end": 600, "measure": "interval" } } }"#;
+		assert_eq!(
+			wasm_text(5_400.0, None, config, WASM_CUSTOM_TEXT),
+			Ok(String::from("١,٥ hrs"))
+		);
+	}
+	#[cfg(feature = "wasm-bindgen")]
+	#[test]
+	fn wasm_start_out_of_range() {
+		// A unit that would only start past `Duration`'s range is left out.
+		let config = r#"{ "units": { "hours": {}, "minutes": { "start": 18446744073709551615, "measure": "interval" } } }"#;
+		assert_eq!(
+			wasm_text(3_660.0, None, config, "{}"),
+			Ok(String::from("1 hour"))
+		);
+	}
+	#[cfg(feature = "wasm-bindgen")]
+	#[test]
+	fn wasm_parse() {
+		use crate::wasm::{from_json, parse_seconds};
+
+		assert_eq!(
+			parse_seconds("1,5 hrs 30s", from_json(WASM_CUSTOM_TEXT).unwrap()).ok(),
+			Some(5_430.0)
+		);
+	}
+	#[cfg(feature = "wasm-bindgen")]
+	#[test]
+	fn wasm_invalid_interval() {
+		assert_eq!(
+			wasm_text(f64::NAN, None, "{}", "{}"),
+			Err(String::from(
+				"The interval must be a finite number of seconds, within range"
+			))
+		);
+	}
+	#[cfg(feature = "wasm-bindgen")]
+	#[test]
+	fn wasm_missing_date() {
+		assert_eq!(
+			wasm_text(60.0, None, r#"{ "units": { "months": {} } }"#, "{}"),
+			Err(StringifyError::MissingReferenceDate.to_string())
+		);
+	}
+	#[cfg(feature = "wasm-bindgen")]
+	#[test]
+	fn wasm_unknown_option() {
+		assert!(
+			crate::wasm::from_json::<crate::wasm::ConfigOptions>(r#"{ "unit": {} }"#)
+				.unwrap_err()
+				.to_string()
+				.starts_with("Invalid options: unknown field `unit`")
+		);
+	}
+	#[cfg(feature = "wasm-bindgen")]
+	#[test]
+	fn wasm_threshold_labels() {
+		// Labels by thresholds, and in a context.
+		let slavic = r#"{
+			"days": { "lowest": "{n} дней", "thresholds": [[1, "{n} день"], [2, "{n} дня"], [5, "{n} дней"], [21, "{n} день"]] },
+			"past": { "phrase": "{interval} back", "hours": "{n}h" }
+		}"#;
+		let config = r#"{ "units": { "days": {}, "hours": {} }, "phrasing": "relative" }"#;
+		assert_eq!(
+			wasm_text(3.0 * 86_400.0 + 7_200.0, None, config, slavic),
+			Ok(String::from("in 3 дня and 2 hours"))
+		);
+		assert_eq!(
+			wasm_text(-21.0 * 86_400.0 - 3_600.0, None, config, slavic),
+			Ok(String::from("21 день and 1h back"))
+		);
+	}
+	#[cfg(feature = "wasm-bindgen")]
+	#[test]
+	fn wasm_fraction_thresholds() {
+		// In French, "1,5 heure", but "2,5 heures".
+		let french = r#"{
+			"hours": { "lowest": "{n} heure", "thresholds": [[2, "{n} heures"]], "fraction": { "lowest": "{n} heure", "thresholds": [[2, "{n} heures"]] } },
+			"numberFormat": { "decimalSeparator": "," }
+		}"#;
+		let config = r#"{ "units": { "hours": { "decimals": 1 } } }"#;
+		assert_eq!(
+			wasm_text(5_400.0, None, config, french),
+			Ok(String::from("1,5 heure"))
+		);
+		assert_eq!(
+			wasm_text(9_000.0, None, config, french),
+			Ok(String::from("2,5 heures"))
+		);
+	}
+	#[cfg(feature = "wasm-bindgen")]
+	#[test]
+	fn wasm_unordered_thresholds() {
+		assert_eq!(
+			wasm_text(
+				60.0,
+				None,
+				"{}",
+				r#"{ "days": { "lowest": "", "thresholds": [[2, "a"], [1, "b"]] } }"#
+			),
+			Err(String::from(
+				"Invalid options: The thresholds of a label must be in increasing order"
+			))
+		);
+	}
+	#[cfg(feature = "wasm-bindgen")]
+	#[test]
+	fn wasm_constant_units() {
+		// Units of its own, between the built-in ones.
+		let config = r#"{ "constantUnits": [
+			{ "name": "days", "display": {} },
+			{ "name": "shifts", "seconds": 28800, "labels": { "standalone": { "one": "{n} shift", "other": "{n} shifts" }, "past": "{n} shifts" }, "display": {} },
+			{ "name": "hours", "display": {} },
+			{ "name": "minutes" }
+		] }"#;
+		assert_eq!(
+			wasm_text(43.0 * 3_600.0 + 60.0, None, config, "{}"),
+			Ok(String::from("1 day, 2 shifts and 3 hours"))
+		);
+	}
+	#[cfg(feature = "wasm-bindgen")]
+	#[test]
+	fn wasm_unknown_constant_unit() {
+		assert_eq!(
+			wasm_text(60.0, None, r#"{ "constantUnits": [{ "name": "fortnights" }] }"#, "{}"),
+			Err(String::from("Invalid options: Unknown unit \"fortnights\", expected weeks, days, hours, minutes or seconds, or a unit with seconds and labels"))
+		);
+	}
+	#[cfg(feature = "wasm-bindgen")]
+	#[test]
+	fn wasm_constant_unit_without_labels() {
+		assert_eq!(
+			wasm_text(60.0, None, r#"{ "constantUnits": [{ "name": "shifts", "seconds": 28800 }] }"#, "{}"),
+			Err(String::from("Invalid options: The unit \"shifts\" needs both seconds and labels, or neither to be a built-in one"))
+		);
+	}
+
+	#[cfg(all(feature = "wasm-bindgen", target_arch = "wasm32"))]
+	#[wasm_bindgen_test::wasm_bindgen_test]
+	fn wasm_bindings() {
+		use serde::Serialize;
+		use wasm_bindgen::JsValue;
+
+		use crate::wasm;
+
+		let object = |json: &str| {
+			serde_json::from_str::<serde_json::Value>(json)
+				.unwrap()
+				.serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+				.unwrap()
+		};
+		let config = r#"{ "units": { "hours": {}, "minutes": {} }, "phrasing": "relative" }"#;
+		let text = r#"{ "preset": "compact" }"#;
+		// Options as objects, as JSON, or left out.
+		assert_eq!(
+			wasm::without_date(-5_400.0, object(config), object(text)).ok(),
+			Some(String::from("1h 30m ago"))
+		);
+		assert_eq!(
+			wasm::without_date(-5_400.0, JsValue::from_str(config), JsValue::from_str(text)).ok(),
+			Some(String::from("1h 30m ago"))
+		);
+		assert_eq!(
+			wasm::without_date(1_234_567.0, JsValue::UNDEFINED, JsValue::NULL).ok(),
+			Some(String::from("14 days, 6 hours and 56 minutes"))
+		);
+		assert_eq!(
+			wasm::with_date(
+				2_678_400.0,
+				"2024-01-01T00:00:00+01:00",
+				JsValue::UNDEFINED,
+				JsValue::UNDEFINED
+			)
+			.ok(),
+			Some(String::from("1 month"))
+		);
+		assert_eq!(wasm::parse("2h5m", object(text)).ok(), Some(7_500.0));
+		assert!(wasm::parse("2 fortnights", JsValue::UNDEFINED).is_err());
+	}
+
+	#[cfg(feature = "serde")]
+	#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+	struct SerdeConfig {
+		#[serde(with = "crate::serde::human")]
+		timeout: Duration,
+		#[serde(with = "crate::serde::compact::option", default)]
+		retry: Option<Duration>,
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn serde_adapters() {
+		let config: SerdeConfig =
+			toml::from_str("timeout = \"1 day and 30 seconds\"\nretry = \"1.5h\"").unwrap();
+		assert_eq!(
+			config,
+			SerdeConfig {
+				timeout: Duration::seconds(86_430),
+				retry: Some(Duration::minutes(90)),
+			}
+		);
+		assert_eq!(
+			toml::to_string(&config).unwrap(),
+			"timeout = \"1 day and 30 seconds\"\nretry = \"1h 30m\"\n"
+		);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn serde_missing_option() {
+		let config: SerdeConfig = toml::from_str("timeout = \"5 minutes ago\"").unwrap();
+		assert_eq!(config.timeout, Duration::minutes(-5));
+		assert_eq!(config.retry, None);
+		assert_eq!(
+			serde_json::to_string(&config).unwrap(),
+			r#"{"timeout":"5 minutes ago","retry":null}"#
+		);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn serde_round_trip() {
+		for interval in [
+			Duration::zero(),
+			Duration::seconds(-1),
+			Duration::seconds(1_234_567_891),
+		] {
+			let config = SerdeConfig {
+				timeout: interval,
+				retry: Some(interval),
+			};
+			let json = serde_json::to_string(&config).unwrap();
+			assert_eq!(
+				serde_json::from_str::<SerdeConfig>(&json).unwrap(),
+				config,
+				"{json}"
+			);
+		}
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn serde_unknown_unit() {
+		let error = toml::from_str::<SerdeConfig>("timeout = \"2 fortnights\"").unwrap_err();
+		assert!(error.message().starts_with("Unknown unit \"fortnights\""));
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn serde_human_reads_compact() {
+		#[derive(serde::Deserialize)]
+		struct Config {
+			#[serde(with = "crate::serde::human")]
+			timeout: Duration,
+		}
+
+		let config: Config = serde_json::from_str(r#"{ "timeout": "2h5m" }"#).unwrap();
+		assert_eq!(config.timeout, Duration::minutes(125));
+	}
+
+	#[cfg(feature = "tokio")]
+	#[tokio::test(start_paused = true)]
+	async fn interval_stream() {
+		use std::pin::Pin;
+
+		use futures_core::Stream;
+		use tokio::time::Instant;
+
+		use crate::IntervalStream;
+
+		let start = Instant::now();
+		let config = DisplayConfig::none()
+			.with_minutes()
+			.with_phrasing(Phrasing::Relative);
+		let mut stream = IntervalStream::new(
+			start + std::time::Duration::from_secs(90),
+			date_year_month_day(2024, 1, 1),
+			config,
+			Text::default(),
+		);
+		// Only yields when the text changes, after sleeping until then.
+		for (expected, after_seconds) in [
+			("in 2 minutes", 0),
+			("in 1 minute", 0),
+			("in 0 minutes", 60),
+			("0 minutes ago", 90),
+			("1 minute ago", 120),
+		] {
+			assert_eq!(
+				std::future::poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await,
+				Some(Ok(String::from(expected)))
+			);
+			assert_eq!(
+				(Instant::now() - start).as_secs(),
+				after_seconds,
+				"{expected}"
+			);
+		}
+	}
+
+	#[cfg(feature = "tokio")]
+	#[test]
+	fn interval_stream_made_outside_runtime() {
+		use std::pin::Pin;
+
+		use futures_core::Stream;
+		use tokio::time::Instant;
+
+		use crate::IntervalStream;
+
+		let mut stream = IntervalStream::new(
+			Instant::now() + std::time::Duration::from_secs(150),
+			date_year_month_day(2024, 1, 1),
+			DisplayConfig::none().with_minutes(),
+			Text::default(),
+		);
+		let runtime = tokio::runtime::Builder::new_current_thread()
+			.enable_time()
+			.build()
+			.unwrap();
+		assert_eq!(
+			runtime.block_on(std::future::poll_fn(|cx| {
+				Pin::new(&mut stream).poll_next(cx)
+			})),
+			Some(Ok(String::from("2 minutes")))
+		);
+	}
+
+	#[test]
+	fn parse_round_trip() {
+		let config = DisplayConfigConstant::none()
+			.with_weeks()
+			.with_days()
+			.with_hours()
+			.with_minutes()
+			.with_seconds()
+			.with_phrasing(Phrasing::Relative);
+		for text in [Text::default(), Text::compact(), Text::german()] {
+			for seconds in [1, 59, 61, 3_600, 90_061, 1_234_567, -7_200, -694_861] {
+				let interval = Duration::seconds(seconds);
+				let output = without_date(interval, &config, &text).unwrap();
+				assert_eq!(parse(&output, &text), Ok(interval), "{output}");
+			}
+		}
+	}
+
+	#[test]
+	fn parse_single_unit() {
+		assert_eq!(parse("2 days", &Text::default()), Ok(Duration::days(2)));
+	}
+
+	#[test]
+	fn parse_lenient_separators() {
+		let text = Text::default();
+		assert_eq!(
+			parse("  1 hour,30 minutes ", &text),
+			Ok(Duration::minutes(90))
+		);
+		assert_eq!(
+			parse("5 minutes and 1 hour", &text),
+			Ok(Duration::minutes(65))
+		);
+	}
+
+	#[test]
+	fn parse_fractions() {
+		let text = Text::default();
+		assert_eq!(parse("3.5 hours", &text), Ok(Duration::minutes(210)));
+		assert_eq!(
+			parse("0.001 seconds", &text),
+			Ok(Duration::milliseconds(1))
+		);
+	}
+
+	#[test]
+	fn parse_phrasing() {
+		let text = Text::default();
+		assert_eq!(parse("for 2 weeks", &text), Ok(Duration::weeks(2)));
+		assert_eq!(parse("in 1 day", &text), Ok(Duration::days(1)));
+		assert_eq!(parse("1 day ago", &text), Ok(Duration::days(-1)));
+	}
+
+	#[test]
+	fn parse_compact() {
+		assert_eq!(
+			parse("1d 2h 3m 4s", &Text::compact()),
+			Ok(Duration::seconds(93_784))
+		);
+	}
+
+	#[test]
+	fn parse_german() {
+		assert_eq!(
+			parse("vor 1,5 Tagen", &Text::german()),
+			Ok(Duration::hours(-36))
+		);
+	}
+
+	#[test]
+	fn parse_empty() {
+		assert_eq!(parse(" ", &Text::compact()), Err(ParseError::Empty));
+	}
+
+	#[test]
+	fn parse_expected_number() {
+		assert_eq!(
+			parse("2h soon", &Text::compact()),
+			Err(ParseError::ExpectedNumber {
+				found: String::from("soon")
+			})
+		);
+	}
+
+	#[test]
+	fn parse_missing_unit() {
+		assert_eq!(
+			parse("1.5", &Text::compact()),
+			Err(ParseError::MissingUnit {
+				number: String::from("1.5")
+			})
+		);
+		let error = parse("2h 5 3m", &Text::compact()).unwrap_err();
+		assert_eq!(error.to_string(), "Expected a unit after \"5\"");
+	}
+
+	#[test]
+	fn parse_unknown_unit() {
+		let error = parse("5min", &Text::compact()).unwrap_err();
+		assert_eq!(
+			error.to_string(),
+			"Unknown unit \"min\", expected one of: w, d, h, m, s"
+		);
+	}
+
+	#[test]
+	fn parse_out_of_range() {
+		assert_eq!(
+			parse("99999999999999999999 weeks", &Text::default()),
+			Err(ParseError::OutOfRange)
+		);
+	}
+
+	#[test]
+	fn german() {
+		let config = DisplayConfig::default().with_phrasing(Phrasing::Relative);
+		let date = date_year_month_day(2024, 1, 1);
+		let text = Text::german();
+		assert_eq!(
+			with_date(Duration::days(-33), date, &config, &text),
+			Ok(String::from("vor 1 Monat und 2 Tagen"))
+		);
+		assert_eq!(
+			with_date(
+				Duration::days(1) + Duration::hours(3),
+				date,
+				&DisplayConfig::default(),
+				&text
+			),
+			Ok(String::from("1 Tag und 3 Stunden"))
+		);
+	}
+
 	#[test]
 	fn zero_duration() {
 		assert_eq!(
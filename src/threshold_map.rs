@@ -1,8 +1,13 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
 /// A map that stores values and thresholds that determine what value belongs to any given number.
 #[derive(Debug, Clone)]
 pub struct ThresholdMap<T> {
 	thresholds: Vec<u64>,
 	values: Vec<T>,
+	/// The values for numbers with a fractional part, by thresholds of their own.
+	fractional: Option<Box<ThresholdMap<T>>>,
 }
 
 impl<T> ThresholdMap<T> {
@@ -15,7 +20,42 @@ impl<T> ThresholdMap<T> {
 		let thresholds = Vec::with_capacity(capacity);
 		let mut values = Vec::with_capacity(capacity + 1);
 		values.push(lowest_value);
-		Self { thresholds, values }
+		Self {
+			thresholds,
+			values,
+			fractional: None,
+		}
+	}
+	/// Sets a value to use for any number with a fractional part, regardless of thresholds. Without it, fractional numbers get the value for their count rounded up, so 1.5 gets the same value as 2.
+	///
+	/// ```
+	/// # use stringify_interval::ThresholdMap;
+	/// #
+	/// let map = ThresholdMap::<String>::from_iter("hours", [(1, "hour"), (2, "hours"), (3, "many hours")])
+	///     .unwrap();
+	/// assert_eq!(map.get_fractional(1, true), &String::from("hours"));
+	/// assert_eq!(map.get_fractional(2, true), &String::from("many hours"));
+	/// let map = map.with_fractional_value("some hours");
+	/// assert_eq!(map.get_fractional(2, true), &String::from("some hours"));
+	/// assert_eq!(map.get_fractional(1, false), &String::from("hour"));
+	/// ```
+	pub fn with_fractional_value<V: Into<T>>(self, value: V) -> Self {
+		self.with_fractional_thresholds(Self::single_value(value))
+	}
+	/// Sets the values for numbers with a fractional part by thresholds of their own, for languages where the form depends on the number itself. A number is over a threshold when its integer part is, so 1.5 is under 2.
+	///
+	/// ```
+	/// # use stringify_interval::ThresholdMap;
+	/// #
+	/// // In French, "1,5 heure", but "2,5 heures".
+	/// let hours = ThresholdMap::<String>::from_iter("heure", [(2, "heures")]).unwrap();
+	/// let map = hours.clone().with_fractional_thresholds(hours);
+	/// assert_eq!(map.get_fractional(1, true), &String::from("heure"));
+	/// assert_eq!(map.get_fractional(2, true), &String::from("heures"));
+	/// ```
+	pub fn with_fractional_thresholds(mut self, fractional: ThresholdMap<T>) -> Self {
+		self.fractional = Some(Box::new(fractional));
+		self
 	}
 	/// Creates a new `ThresholdMap` from an iterator yielding thresholds and values applying at or over those thresholds.
 	///
@@ -76,6 +116,20 @@ impl<T> ThresholdMap<T> {
 			.unwrap_or(self.thresholds.len());
 		&self.values[index]
 	}
+	/// Gets the value for a number with integer part `whole`, which has a nonzero fractional part if `has_fraction` is true. Such a number gets the value from the fractional thresholds for its integer part, or else the value for its count rounded up.
+	pub fn get_fractional(&self, whole: u64, has_fraction: bool) -> &T {
+		match &self.fractional {
+			_ if !has_fraction => self.get(whole),
+			Some(fractional) => fractional.get(whole),
+			None => self.get(whole.saturating_add(1)),
+		}
+	}
+	/// All the values, including those for fractional numbers.
+	pub(crate) fn values(&self) -> impl Iterator<Item = &T> {
+		self.values
+			.iter()
+			.chain(self.fractional.iter().flat_map(|fractional| &fractional.values))
+	}
 	fn get_index(&self, key: &u64) -> Result<usize, usize> {
 		self.thresholds.binary_search(key)
 	}
@@ -1,6 +1,11 @@
+use alloc::string::String;
+
 #[derive(Debug, Default, Clone, Copy)]
 pub(crate) struct UnitValues<T> {
+	pub centuries: T,
+	pub decades: T,
 	pub years: T,
+	pub quarters: T,
 	pub months: T,
 	pub weeks: T,
 	pub days: T,
@@ -10,11 +15,12 @@ pub(crate) struct UnitValues<T> {
 }
 
 impl<T> UnitValues<T> {
-	pub(crate) fn iter(
-		&self,
-	) -> impl Iterator<Item = &T> + ExactSizeIterator + DoubleEndedIterator {
+	pub(crate) fn iter(&self) -> impl ExactSizeIterator<Item = &T> + DoubleEndedIterator {
 		[
+			&self.centuries,
+			&self.decades,
 			&self.years,
+			&self.quarters,
 			&self.months,
 			&self.weeks,
 			&self.days,
@@ -26,7 +32,10 @@ impl<T> UnitValues<T> {
 	}
 	pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
 		[
+			&mut self.centuries,
+			&mut self.decades,
 			&mut self.years,
+			&mut self.quarters,
 			&mut self.months,
 			&mut self.weeks,
 			&mut self.days,
@@ -37,3 +46,58 @@ impl<T> UnitValues<T> {
 		.into_iter()
 	}
 }
+
+/// One of the units an interval can be split into.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Unit {
+	Centuries,
+	Decades,
+	Years,
+	Quarters,
+	Months,
+	Weeks,
+	Days,
+	Hours,
+	Minutes,
+	Seconds,
+	/// A unit from `DisplayConfig::constant_units` that isn't one of the built-in ones, by its name.
+	Custom(String),
+}
+
+impl Unit {
+	/// The number of calendar units, which come first in `ALL`.
+	pub(crate) const CALENDAR_COUNT: usize = 5;
+	/// All units, from largest to smallest, in the same order as `UnitValues::iter`.
+	pub(crate) const ALL: [Self; 10] = [
+		Self::Centuries,
+		Self::Decades,
+		Self::Years,
+		Self::Quarters,
+		Self::Months,
+		Self::Weeks,
+		Self::Days,
+		Self::Hours,
+		Self::Minutes,
+		Self::Seconds,
+	];
+}
+
+impl core::fmt::Display for Unit {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		let name = match self {
+			Self::Centuries => "centuries",
+			Self::Decades => "decades",
+			Self::Years => "years",
+			Self::Quarters => "quarters",
+			Self::Months => "months",
+			Self::Weeks => "weeks",
+			Self::Days => "days",
+			Self::Hours => "hours",
+			Self::Minutes => "minutes",
+			Self::Seconds => "seconds",
+			Self::Custom(name) => name,
+		};
+		f.write_str(name)
+	}
+}
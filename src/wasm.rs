@@ -0,0 +1,601 @@
+//! Bindings for JavaScript, so a browser can write the same text as Rust code with the same options.
+//!
+//! The options are plain objects, or the same as JSON strings, in this shape, where everything can be left out:
+//!
+//! ```text
+//! config: {
+//!     units: { hours: {}, minutes: { start: 0, end: 60, pad: 2, displayZero: true, decimals: 1 }, seconds: { end: 600, measure: "interval" } },
+//!     phrasing: "standalone" | "relative" | "duration",
+//!     monthArithmetic: "clamp" | "overflow" | "dayReached",
+//!     anchor: "referenceDate" | "start" | "end",
+//!     calendarDays: false,
+//!     constantUnits: [{ name: "days", display: {} }, { name: "shifts", seconds: 28800, labels: { one: "{n} shift", other: "{n} shifts" }, display: {} }],
+//! }
+//! text: {
+//!     preset: "english" | "compact" | "german",
+//!     hours: "{n}h" | { one: "{n} hour", other: "{n} hours" } | { lowest: "{n} hours", thresholds: [[1, "{n} hour"], [2, "{n} hours"]], fraction: "{n} hours" | { lowest: "{n} hour", thresholds: [[2, "{n} hours"]] } },
+//!     joiner: ", ",
+//!     finalJoiner: " and " | null,
+//!     future: "in {interval}" | { phrase: "in {interval}", days: { one: "{n} Tag", other: "{n} Tagen" } },
+//!     past: "{interval} ago", duration: "for {interval}",
+//!     numberFormat: { groupingSeparator: "," | null, groupingSize: 3, decimalSeparator: ".", digits: "0123456789" },
+//! }
+//! ```
+//!
+//! Without `units`, the units are those of `DisplayConfig::default` with a date, and of `DisplayConfigConstant::default` without one. A unit's range goes from `start` up to but not including `end`, and is on its own count, or on the whole interval in seconds with `measure: "interval"`.
+//!
+//! A label is the same for every count, has a singular for a count of 1, or has a list of thresholds in increasing order, each with the label from that count up, and `lowest` below the first one, like `ThresholdMap`. With thresholds, `fraction` is the label for counts with decimals, which can have thresholds of its own on their integer part. The `future`, `past` and `duration` phrases can come with the units' labels in that context, where they differ from the standalone ones.
+//!
+//! `constantUnits` replaces weeks to seconds, in `units` and in the text, like `DisplayConfig::constant_units`. A unit with just a name is the built-in one, from weeks to seconds, with its label from the text, and a unit with `seconds` and `labels` is a unit of its own. Its labels are a label, or `{ standalone, future, past, duration }` with a label for each context, where all but `standalone` can be left out. Units without `display` are not displayed.
+//!
+//! To build a module, depend on the crate with the feature from a `cdylib` crate that refers to it, like with `pub use stringify_interval;`, and run `wasm-bindgen` on the output. The bindings are tested in node with `CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUNNER=wasm-bindgen-test-runner cargo test --lib --features wasm-bindgen --target wasm32-unknown-unknown`.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use ::serde::de::DeserializeOwned;
+use ::serde::{Deserialize, Deserializer};
+use chrono::{DateTime, Duration};
+use wasm_bindgen::prelude::*;
+
+use crate::errors::{ParseError, StringifyError};
+use crate::number_format::NumberFormat;
+use crate::options::{
+	plural, Anchor, ConstantUnit, ContextText, DisplayConfig, DisplayRange, DisplaySettings,
+	MonthArithmetic, Phrasing, Text, UnitLabels,
+};
+use crate::threshold_map::ThresholdMap;
+
+/// Stringifies an interval in seconds like `without_date`, so calendar units like years and months can't be included.
+#[wasm_bindgen(js_name = withoutDate)]
+pub fn without_date(seconds: f64, config: JsValue, text: JsValue) -> Result<String, JsError> {
+	Ok(stringify(seconds, None, from_js(config)?, from_js(text)?)?)
+}
+
+/// Stringifies an interval in seconds like `with_date`, from a date in RFC 3339, like `Date.toISOString()` gives, with years and months counted in its offset.
+#[wasm_bindgen(js_name = withDate)]
+pub fn with_date(
+	seconds: f64,
+	date: &str,
+	config: JsValue,
+	text: JsValue,
+) -> Result<String, JsError> {
+	Ok(stringify(
+		seconds,
+		Some(date),
+		from_js(config)?,
+		from_js(text)?,
+	)?)
+}
+
+/// Parses text like "2 hours and 5 minutes" into seconds, like `parse`.
+#[wasm_bindgen(js_name = parse)]
+pub fn parse(input: &str, text: JsValue) -> Result<f64, JsError> {
+	Ok(parse_seconds(input, from_js(text)?)?)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum BindingError {
+	#[error("Invalid options: {0}")]
+	Options(String),
+	#[error("The interval must be a finite number of seconds, within range")]
+	Seconds,
+	#[error("Invalid date \"{0}\", expected one in RFC 3339")]
+	Date(String),
+	#[error(transparent)]
+	Stringify(#[from] StringifyError),
+	#[error(transparent)]
+	Parse(#[from] ParseError),
+}
+
+/// Reads options from a plain object or a JSON string, or their defaults from `undefined` or `null`.
+fn from_js<T: DeserializeOwned + Default>(value: JsValue) -> Result<T, BindingError> {
+	if value.is_undefined() || value.is_null() {
+		return Ok(T::default());
+	}
+	match value.as_string() {
+		Some(json) => from_json(&json),
+		None => serde_wasm_bindgen::from_value(value)
+			.map_err(|error| BindingError::Options(error.to_string())),
+	}
+}
+
+pub(crate) fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, BindingError> {
+	serde_json::from_str(json).map_err(|error| BindingError::Options(error.to_string()))
+}
+
+pub(crate) fn stringify(
+	seconds: f64,
+	date: Option<&str>,
+	config: ConfigOptions,
+	text: TextOptions,
+) -> Result<String, BindingError> {
+	let interval = interval(seconds)?;
+	let text = text.into_text()?;
+	let config = config.into_config(date.is_some(), &text)?;
+	Ok(match date {
+		Some(date) => {
+			let date =
+				DateTime::parse_from_rfc3339(date).map_err(|_| BindingError::Date(date.into()))?;
+			crate::with_date(interval, date, &config, &text)?
+		}
+		None => crate::stringify::stringify_interval(interval, None, (&config).into(), &text)?,
+	})
+}
+
+pub(crate) fn parse_seconds(input: &str, text: TextOptions) -> Result<f64, BindingError> {
+	let interval = crate::parse(input, &text.into_text()?)?;
+	Ok(interval.num_seconds() as f64 + f64::from(interval.subsec_nanos()) / 1e9)
+}
+
+/// A number of seconds from JavaScript, to the nanosecond.
+fn interval(seconds: f64) -> Result<Duration, BindingError> {
+	// `i64::MAX` as a float rounds up past it, so that one is out of range too.
+	if !seconds.is_finite() || seconds.abs() >= i64::MAX as f64 {
+		return Err(BindingError::Seconds);
+	}
+	let whole = seconds.trunc();
+	let nanoseconds = ((seconds - whole) * 1e9).round() as i64;
+	Duration::try_seconds(whole as i64)
+		.and_then(|whole| whole.checked_add(&Duration::nanoseconds(nanoseconds)))
+		.ok_or(BindingError::Seconds)
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct ConfigOptions {
+	units: Option<UnitsOptions>,
+	phrasing: PhrasingOption,
+	month_arithmetic: MonthArithmeticOption,
+	anchor: AnchorOption,
+	calendar_days: bool,
+	constant_units: Option<Vec<ConstantUnitOptions>>,
+}
+
+impl ConfigOptions {
+	fn into_config(self, with_date: bool, text: &Text) -> Result<DisplayConfig, BindingError> {
+		let mut config = match self.units {
+			Some(units) => DisplayConfig {
+				centuries: units.centuries.and_then(UnitOptions::into_settings),
+				decades: units.decades.and_then(UnitOptions::into_settings),
+				years: units.years.and_then(UnitOptions::into_settings),
+				quarters: units.quarters.and_then(UnitOptions::into_settings),
+				months: units.months.and_then(UnitOptions::into_settings),
+				weeks: units.weeks.and_then(UnitOptions::into_settings),
+				days: units.days.and_then(UnitOptions::into_settings),
+				hours: units.hours.and_then(UnitOptions::into_settings),
+				minutes: units.minutes.and_then(UnitOptions::into_settings),
+				seconds: units.seconds.and_then(UnitOptions::into_settings),
+				..DisplayConfig::none()
+			},
+			None if with_date => DisplayConfig::default(),
+			None => DisplayConfig::default_no_inconstant(),
+		};
+		config.phrasing = match self.phrasing {
+			PhrasingOption::Standalone => Phrasing::Standalone,
+			PhrasingOption::Relative => Phrasing::Relative,
+			PhrasingOption::Duration => Phrasing::Duration,
+		};
+		config.month_arithmetic = match self.month_arithmetic {
+			MonthArithmeticOption::Clamp => MonthArithmetic::Clamp,
+			MonthArithmeticOption::Overflow => MonthArithmetic::Overflow,
+			MonthArithmeticOption::DayReached => MonthArithmetic::DayReached,
+		};
+		config.anchor = match self.anchor {
+			AnchorOption::ReferenceDate => Anchor::ReferenceDate,
+			AnchorOption::Start => Anchor::Start,
+			AnchorOption::End => Anchor::End,
+		};
+		config.calendar_days = self.calendar_days;
+		if let Some(units) = self.constant_units {
+			let built_in = DisplayConfig::none().built_in_units(text);
+			config = config.with_constant_units(
+				units
+					.into_iter()
+					.map(|unit| unit.into_unit(&built_in))
+					.collect::<Result<_, _>>()?,
+			);
+		}
+		Ok(config)
+	}
+}
+
+/// The units to display. Those left out aren't displayed.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct UnitsOptions {
+	centuries: Option<UnitOptions>,
+	decades: Option<UnitOptions>,
+	years: Option<UnitOptions>,
+	quarters: Option<UnitOptions>,
+	months: Option<UnitOptions>,
+	weeks: Option<UnitOptions>,
+	days: Option<UnitOptions>,
+	hours: Option<UnitOptions>,
+	minutes: Option<UnitOptions>,
+	seconds: Option<UnitOptions>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+struct UnitOptions {
+	start: u64,
+	/// Exclusive.
+	end: Option<u64>,
+	measure: Measure,
+	pad: u8,
+	display_zero: bool,
+	decimals: u8,
+}
+
+impl UnitOptions {
+	/// The settings, or none if the unit can never be displayed.
+	fn into_settings(self) -> Option<DisplaySettings> {
+		let range = match (self.measure, self.end) {
+			(Measure::Count, Some(end)) => DisplayRange::from(self.start..end),
+			(Measure::Count, None) => DisplayRange::from(self.start..),
+			(Measure::Interval, end) => {
+				let seconds =
+					|bound: u64| i64::try_from(bound).ok().and_then(Duration::try_seconds);
+				// Bounds past `Duration`'s range are never reached, so a unit that starts there is left out.
+				let start = seconds(self.start)?;
+				match end.map(seconds) {
+					Some(Some(end)) => DisplayRange::interval(start..end),
+					_ => DisplayRange::interval(start..),
+				}
+			}
+		};
+		Some(DisplaySettings::new(range, self.pad, self.display_zero).with_decimals(self.decimals))
+	}
+}
+
+/// A unit in `constantUnits`, either a built-in one by its name, or one of its own with a length and labels.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConstantUnitOptions {
+	name: String,
+	seconds: Option<i64>,
+	labels: Option<UnitLabelsOptions>,
+	display: Option<UnitOptions>,
+}
+
+impl ConstantUnitOptions {
+	fn into_unit(self, built_in: &[ConstantUnit]) -> Result<ConstantUnit, BindingError> {
+		let settings = self.display.and_then(UnitOptions::into_settings);
+		let mut unit = match (self.seconds, self.labels) {
+			(None, None) => built_in
+				.iter()
+				.find(|unit| unit.name == self.name)
+				.cloned()
+				.ok_or_else(|| {
+					BindingError::Options(format!(
+						"Unknown unit \"{}\", expected weeks, days, hours, minutes or seconds, or a unit with seconds and labels",
+						self.name
+					))
+				})?,
+			(Some(seconds), Some(labels)) => {
+				let length = Duration::try_seconds(seconds).ok_or_else(|| {
+					BindingError::Options(format!("The length of {} is out of range", self.name))
+				})?;
+				ConstantUnit::new(
+					self.name,
+					length,
+					DisplaySettings::new(0.., 0, false),
+					labels.into_labels()?,
+				)
+			}
+			_ => {
+				return Err(BindingError::Options(format!(
+					"The unit \"{}\" needs both seconds and labels, or neither to be a built-in one",
+					self.name
+				)))
+			}
+		};
+		unit.settings = settings;
+		Ok(unit)
+	}
+}
+
+/// The labels of a unit of its own, the same in every context or not.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum UnitLabelsOptions {
+	Contexts(Box<ContextLabels>),
+	Same(Label),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ContextLabels {
+	standalone: Label,
+	future: Option<Label>,
+	past: Option<Label>,
+	duration: Option<Label>,
+}
+
+impl UnitLabelsOptions {
+	fn into_labels(self) -> Result<UnitLabels, BindingError> {
+		let labels = match self {
+			Self::Same(label) => label.into_map()?.into(),
+			Self::Contexts(labels) => UnitLabels {
+				standalone: labels.standalone.into_map()?,
+				future: labels.future.map(Label::into_map).transpose()?,
+				past: labels.past.map(Label::into_map).transpose()?,
+				duration: labels.duration.map(Label::into_map).transpose()?,
+			},
+		};
+		Ok(labels)
+	}
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum Measure {
+	#[default]
+	Count,
+	Interval,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum PhrasingOption {
+	#[default]
+	Standalone,
+	Relative,
+	Duration,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum MonthArithmeticOption {
+	#[default]
+	Clamp,
+	Overflow,
+	DayReached,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum AnchorOption {
+	#[default]
+	ReferenceDate,
+	Start,
+	End,
+}
+
+/// A preset, with any of its strings replaced.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct TextOptions {
+	preset: Preset,
+	centuries: Option<Label>,
+	decades: Option<Label>,
+	years: Option<Label>,
+	quarters: Option<Label>,
+	months: Option<Label>,
+	weeks: Option<Label>,
+	days: Option<Label>,
+	hours: Option<Label>,
+	minutes: Option<Label>,
+	seconds: Option<Label>,
+	joiner: Option<String>,
+	/// Present but `null` to always use the joiner.
+	#[serde(deserialize_with = "present")]
+	final_joiner: Option<Option<String>>,
+	future: Option<ContextOptions>,
+	past: Option<ContextOptions>,
+	duration: Option<ContextOptions>,
+	number_format: Option<NumberFormatOptions>,
+}
+
+impl TextOptions {
+	fn into_text(self) -> Result<Text, BindingError> {
+		let mut text = match self.preset {
+			Preset::English => Text::default(),
+			Preset::Compact => Text::compact(),
+			Preset::German => Text::german(),
+		};
+		for (label, map) in [
+			(self.centuries, &mut text.centuries),
+			(self.decades, &mut text.decades),
+			(self.years, &mut text.years),
+			(self.quarters, &mut text.quarters),
+			(self.months, &mut text.months),
+			(self.weeks, &mut text.weeks),
+			(self.days, &mut text.days),
+			(self.hours, &mut text.hours),
+			(self.minutes, &mut text.minutes),
+			(self.seconds, &mut text.seconds),
+		] {
+			if let Some(label) = label {
+				*map = label.into_map()?;
+			}
+		}
+		if let Some(joiner) = self.joiner {
+			text.joiner = joiner;
+		}
+		if let Some(final_joiner) = self.final_joiner {
+			text.final_joiner = final_joiner;
+		}
+		for (options, context) in [
+			(self.future, &mut text.future),
+			(self.past, &mut text.past),
+			(self.duration, &mut text.duration),
+		] {
+			if let Some(options) = options {
+				options.apply(context)?;
+			}
+		}
+		if let Some(number_format) = self.number_format {
+			number_format.apply(&mut text.number_format)?;
+		}
+		Ok(text)
+	}
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum Preset {
+	#[default]
+	English,
+	Compact,
+	German,
+}
+
+/// The phrase of a grammatical context, or the phrase and the units' text in it, where it differs from the standalone text.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ContextOptions {
+	Phrase(String),
+	Text(Box<ContextTextOptions>),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ContextTextOptions {
+	phrase: Option<String>,
+	centuries: Option<Label>,
+	decades: Option<Label>,
+	years: Option<Label>,
+	quarters: Option<Label>,
+	months: Option<Label>,
+	weeks: Option<Label>,
+	days: Option<Label>,
+	hours: Option<Label>,
+	minutes: Option<Label>,
+	seconds: Option<Label>,
+}
+
+impl ContextOptions {
+	fn apply(self, context: &mut ContextText) -> Result<(), BindingError> {
+		let options = match self {
+			Self::Phrase(phrase) => {
+				context.phrase = phrase;
+				return Ok(());
+			}
+			Self::Text(options) => options,
+		};
+		if let Some(phrase) = options.phrase {
+			context.phrase = phrase;
+		}
+		for (label, map) in [
+			(options.centuries, &mut context.centuries),
+			(options.decades, &mut context.decades),
+			(options.years, &mut context.years),
+			(options.quarters, &mut context.quarters),
+			(options.months, &mut context.months),
+			(options.weeks, &mut context.weeks),
+			(options.days, &mut context.days),
+			(options.hours, &mut context.hours),
+			(options.minutes, &mut context.minutes),
+			(options.seconds, &mut context.seconds),
+		] {
+			if let Some(label) = label {
+				*map = Some(label.into_map()?);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// The text for a unit, either the same for every count, with a singular for a count of 1, or by thresholds like in `ThresholdMap`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Label {
+	Same(String),
+	Plural(PluralLabel),
+	Thresholds(ThresholdsLabel),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PluralLabel {
+	one: String,
+	other: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThresholdsLabel {
+	/// Below the first threshold.
+	lowest: String,
+	thresholds: Vec<(u64, String)>,
+	/// For counts with a fractional part, by their integer part like `ThresholdMap::with_fractional_thresholds` when it has thresholds of its own. Without it, they get the label for their count rounded up.
+	fraction: Option<Box<Label>>,
+}
+
+impl Label {
+	fn into_map(self) -> Result<ThresholdMap<String>, BindingError> {
+		Ok(match self {
+			Self::Same(label) => ThresholdMap::single_value(label),
+			Self::Plural(PluralLabel { one, other }) => plural(&one, &other),
+			Self::Thresholds(ThresholdsLabel {
+				lowest,
+				thresholds,
+				fraction,
+			}) => {
+				let map = ThresholdMap::from_iter(lowest, thresholds).ok_or_else(|| {
+					BindingError::Options(String::from(
+						"The thresholds of a label must be in increasing order",
+					))
+				})?;
+				match fraction {
+					Some(fraction) => map.with_fractional_thresholds(fraction.into_map()?),
+					None => map,
+				}
+			}
+		})
+	}
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+struct NumberFormatOptions {
+	#[serde(deserialize_with = "present")]
+	grouping_separator: Option<Option<String>>,
+	grouping_size: Option<u8>,
+	decimal_separator: Option<String>,
+	/// The digits 0 through 9, in order.
+	digits: Option<String>,
+}
+
+impl NumberFormatOptions {
+	fn apply(self, format: &mut NumberFormat) -> Result<(), BindingError> {
+		if let Some(separator) = self.grouping_separator {
+			format.grouping_separator = separator;
+		}
+		if let Some(size) = self.grouping_size {
+			format.grouping_size = size;
+		}
+		if let Some(separator) = self.decimal_separator {
+			format.decimal_separator = separator;
+		}
+		if let Some(digits) = self.digits {
+			let mut chars = digits.chars();
+			for digit in &mut format.digits {
+				*digit = chars.next().ok_or_else(|| wrong_digits(&digits))?;
+			}
+			if chars.next().is_some() {
+				return Err(wrong_digits(&digits));
+			}
+		}
+		Ok(())
+	}
+}
+
+fn wrong_digits(digits: &str) -> BindingError {
+	BindingError::Options(format!(
+		"Expected the 10 digits from 0 to 9, but found \"{digits}\""
+	))
+}
+
+/// Tells a field that is `null` apart from one that is left out, which is `None` by `#[serde(default)]`.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+	D: Deserializer<'de>,
+	T: Deserialize<'de>,
+{
+	Option::<T>::deserialize(deserializer).map(Some)
+}
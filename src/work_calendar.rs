@@ -0,0 +1,145 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+
+use crate::calendar::Zone;
+use crate::errors::StringifyError;
+use crate::options::DisplayConfigRef;
+use crate::util::Unit;
+
+/// Which days are working days, and the working hours on them, for counting business time with `business_time`.
+pub trait WorkCalendar {
+	/// The working hours on the given day, in the reference date's local time, or `None` if it isn't a working day, like on weekends and holidays. Hours that end before they start are no working time at all.
+	fn working_hours(&self, date: NaiveDate) -> Option<Range<NaiveTime>>;
+	/// The working time that makes one business day, which days are displayed in. 8 hours by default.
+	fn business_day(&self) -> Duration {
+		Duration::hours(8)
+	}
+	/// The working time that makes one business week, which weeks are displayed in. 5 business days by default.
+	fn business_week(&self) -> Duration {
+		self.business_day() * 5
+	}
+}
+
+/// The same working hours on every day but the weekend and the holidays.
+///
+/// By default, that is 09:00 to 17:00, from Monday to Friday, with no holidays.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkWeek {
+	pub weekend: Vec<Weekday>,
+	pub holidays: Vec<NaiveDate>,
+	pub hours: Range<NaiveTime>,
+}
+
+impl WorkWeek {
+	pub fn new(weekend: Vec<Weekday>, holidays: Vec<NaiveDate>, hours: Range<NaiveTime>) -> Self {
+		Self {
+			weekend,
+			holidays,
+			hours,
+		}
+	}
+	pub fn with_holiday(mut self, date: NaiveDate) -> Self {
+		self.holidays.push(date);
+		self
+	}
+}
+
+impl Default for WorkWeek {
+	fn default() -> Self {
+		let hour = |hour| NaiveTime::from_hms_opt(hour, 0, 0).unwrap_or_default();
+		Self {
+			weekend: vec![Weekday::Sat, Weekday::Sun],
+			holidays: Vec::new(),
+			hours: hour(9)..hour(17),
+		}
+	}
+}
+
+impl WorkCalendar for WorkWeek {
+	fn working_hours(&self, date: NaiveDate) -> Option<Range<NaiveTime>> {
+		(!self.weekend.contains(&date.weekday()) && !self.holidays.contains(&date))
+			.then(|| self.hours.clone())
+	}
+	/// The length of the working hours.
+	fn business_day(&self) -> Duration {
+		(self.hours.end - self.hours.start).max(Duration::zero())
+	}
+	/// A business day for each day that isn't in the weekend.
+	fn business_week(&self) -> Duration {
+		let working_days = core::iter::successors(Some(Weekday::Mon), |day| Some(day.succ()))
+			.take(7)
+			.filter(|day| !self.weekend.contains(day))
+			.count();
+		self.business_day() * working_days as i32
+	}
+}
+
+/// The lengths of a business week and day in seconds, which replace those of weeks and days. `None` for a unit that isn't displayed, which keeps its usual length.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BusinessLengths {
+	pub(crate) week: Option<u64>,
+	pub(crate) day: Option<u64>,
+}
+
+impl BusinessLengths {
+	/// Only the lengths of the units `config` displays are checked, so a calendar without working days can still count hours.
+	pub(crate) fn new(
+		calendar: &dyn WorkCalendar,
+		config: DisplayConfigRef,
+	) -> Result<Self, StringifyError> {
+		let seconds = |unit: Unit, length: Duration| {
+			let displayed = config
+				.iter_settings()
+				.any(|(settings, other)| settings.is_some() && other == unit);
+			if !displayed {
+				return Ok(None);
+			}
+			u64::try_from(length.num_seconds())
+				.ok()
+				.filter(|&seconds| seconds > 0)
+				.map(Some)
+				.ok_or(StringifyError::InvalidLength { unit, length })
+		};
+		Ok(Self {
+			week: seconds(Unit::Weeks, calendar.business_week())?,
+			day: seconds(Unit::Days, calendar.business_day())?,
+		})
+	}
+}
+
+/// The longest interval `working_time` counts, 100 years of days. Days are gone through one by one, so longer ones are out of range rather than slow.
+const MAX_WORKING_DAYS: i64 = 36_525;
+
+/// The working time between two moments, going through each day between them in the time zone. Negative if `to` is before `from`, and `None` if they are more than `MAX_WORKING_DAYS` apart.
+pub(crate) fn working_time(
+	from: DateTime<Utc>,
+	to: DateTime<Utc>,
+	zone: &dyn Zone,
+	calendar: &dyn WorkCalendar,
+) -> Option<Duration> {
+	let (start, end) = if from <= to { (from, to) } else { (to, from) };
+	if end - start > Duration::days(MAX_WORKING_DAYS) {
+		return None;
+	}
+	let mut total = Duration::zero();
+	let last_day = zone.local(end)?.date();
+	for day in zone
+		.local(start)?
+		.date()
+		.iter_days()
+		.take_while(|day| *day <= last_day)
+	{
+		let Some(hours) = calendar.working_hours(day) else {
+			continue;
+		};
+		let work_start = zone.moment(day.and_time(hours.start))?.max(start);
+		let work_end = zone.moment(day.and_time(hours.end))?.min(end);
+		if work_end > work_start {
+			total = total.checked_add(&(work_end - work_start))?;
+		}
+	}
+	Some(if from <= to { total } else { -total })
+}